@@ -5,7 +5,7 @@
 use crate::service::FullClient;
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, SystemCall, UtilityCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use sp_core::{Encode, Pair};
@@ -92,6 +92,52 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// Generates `Utility::BatchAll` extrinsics of `TemplateModule::DoSomething` calls for the
+/// benchmarks.
+///
+/// Note: Should only be used for benchmarking.
+pub struct BatchBuilder {
+	client: Arc<FullClient>,
+	calls: u32,
+}
+
+impl BatchBuilder {
+	/// Creates a new [`Self`] from the given client, batching `calls` calls per extrinsic.
+	pub fn new(client: Arc<FullClient>, calls: u32) -> Self {
+		Self { client, calls }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for BatchBuilder {
+	fn pallet(&self) -> &str {
+		"utility"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"batch_all"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let acc = Sr25519Keyring::Bob.pair();
+		let calls: Vec<runtime::RuntimeCall> = (0..self.calls)
+			.map(|something| {
+				runtime::RuntimeCall::TemplateModule(runtime::pallet_template::Call::do_something {
+					something,
+				})
+			})
+			.collect();
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			UtilityCall::batch_all { calls }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -120,7 +166,8 @@ pub fn create_benchmark_extrinsic(
 		)),
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
-		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		pallet_asset_tx_payment::ChargeAssetTxPayment::<runtime::Runtime>::from(0, None),
+		pallet_tx_pause::CheckTxPause::<runtime::Runtime>::new(),
 	);
 
 	let raw_payload = runtime::SignedPayload::from_raw(
@@ -135,6 +182,7 @@ pub fn create_benchmark_extrinsic(
 			(),
 			(),
 			(),
+			(),
 		),
 	);
 	let signature = raw_payload.using_encoded(|e| sender.sign(e));
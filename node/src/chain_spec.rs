@@ -1,11 +1,15 @@
+use crate::network_profile::NetworkProfile;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	opaque::SessionKeys, AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber,
+	CouncilConfig, GenesisIdentity, GrandpaConfig, IdentityGenesisConfig, RuntimeGenesisConfig,
+	SessionConfig, Signature, SudoConfig, SystemConfig, TechnicalCommitteeConfig,
+	TemplateModuleConfig, TreasuryAccount, ValidatorSetConfig, VestingGenesisConfig, SS58_PREFIX,
+	TOKEN_DECIMALS, TOKEN_SYMBOL, WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_service::{ChainType, Properties};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{sr25519, Get, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
@@ -14,6 +18,12 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig>;
 
+/// Initial balance of each pre-funded account in the development and local testnet chains.
+const ENDOWMENT: Balance = 1 << 60;
+
+/// Initial balance of the treasury account, unless a network profile endows it explicitly.
+const TREASURY_ENDOWMENT: Balance = 1 << 60;
+
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
 	TPublic::Pair::from_string(&format!("//{}", seed), None)
@@ -31,9 +41,42 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Generate an identity displaying `name`.
+fn identity_from_name(name: &str) -> GenesisIdentity {
+	GenesisIdentity { display: name.as_bytes().to_vec(), ..Default::default() }
+}
+
+/// Generate a validator account and its Aura and GRANDPA session keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
+	(
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<AuraId>(s),
+		get_from_seed::<GrandpaId>(s),
+	)
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
+}
+
+/// Chain properties of the development and local testnet chains, taken from the runtime.
+pub fn chain_properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), SS58_PREFIX.into());
+	properties
+}
+
+/// Ensure the SS58 format advertised by `chain_spec` is the runtime's `SS58Prefix`.
+pub fn check_properties(chain_spec: &dyn sc_service::ChainSpec) -> Result<(), String> {
+	match chain_spec.properties().get("ss58Format") {
+		Some(format) if format.as_u64() != Some(SS58_PREFIX.into()) => Err(format!(
+			"Chain spec `ss58Format` is {} but the runtime's `SS58Prefix` is {}",
+			format, SS58_PREFIX
+		)),
+		_ => Ok(()),
+	}
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
@@ -51,14 +94,33 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice")],
 				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				Some(get_account_id_from_seed::<sr25519::Public>("Alice")),
+				// Council
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
+				// Technical committee
+				vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-				],
+				]
+				.into_iter()
+				.map(|k| (k, ENDOWMENT))
+				.collect(),
+				// Vesting schedules
+				vec![],
+				// Identities
+				vec![(
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					identity_from_name("Alice"),
+				)],
+				// Initial template pallet state
+				TemplateModuleConfig { something: Some(0), ..Default::default() },
 				true,
 			)
 		},
@@ -70,7 +132,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		None,
 		None,
 		// Properties
-		None,
+		Some(chain_properties()),
 		// Extensions
 		None,
 	))
@@ -90,8 +152,19 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				wasm_binary,
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// No sudo outside of development chains
+				None,
+				// Council
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
+				// Technical committee
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				// Pre-funded accounts
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -106,7 +179,24 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-				],
+				]
+				.into_iter()
+				.map(|k| (k, ENDOWMENT))
+				.collect(),
+				// Vesting schedules
+				vec![],
+				// Identities
+				["Alice", "Bob"]
+					.into_iter()
+					.map(|name| {
+						(
+							get_account_id_from_seed::<sr25519::Public>(name),
+							identity_from_name(name),
+						)
+					})
+					.collect(),
+				// Initial template pallet state
+				TemplateModuleConfig { something: Some(0), ..Default::default() },
 				true,
 			)
 		},
@@ -116,9 +206,56 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		None,
 		// Protocol ID
 		None,
+		None,
 		// Properties
+		Some(chain_properties()),
+		// Extensions
+		None,
+	))
+}
+
+/// Build a chain spec from a [`NetworkProfile`].
+pub fn profile_config(profile: NetworkProfile) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+
+	let initial_authorities = profile.authority_keys()?;
+	let root_key = profile.sudo_account()?;
+	let council = profile.council_members()?;
+	let technical_committee = profile.technical_committee_members()?;
+	let endowed_accounts = profile.endowed_accounts()?;
+	let vesting = profile.vesting_schedules()?;
+	let identities = profile.genesis_identities()?;
+	let properties = profile.properties();
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		&profile.name,
+		// ID
+		&profile.id,
+		profile.chain_type,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				initial_authorities.clone(),
+				root_key.clone(),
+				council.clone(),
+				technical_committee.clone(),
+				endowed_accounts.clone(),
+				vesting.clone(),
+				identities.clone(),
+				TemplateModuleConfig { something: Some(0), ..Default::default() },
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
 		None,
 		None,
+		// Properties
+		Some(properties),
 		// Extensions
 		None,
 	))
@@ -127,11 +264,21 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
+	root_key: Option<AccountId>,
+	council: Vec<AccountId>,
+	technical_committee: Vec<AccountId>,
+	mut endowed_accounts: Vec<(AccountId, Balance)>,
+	vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance, Balance)>,
+	identities: Vec<(AccountId, GenesisIdentity)>,
+	template_module: TemplateModuleConfig,
 	_enable_println: bool,
 ) -> RuntimeGenesisConfig {
+	let treasury = TreasuryAccount::get();
+	if !endowed_accounts.iter().any(|(account, _)| *account == treasury) {
+		endowed_accounts.push((treasury, TREASURY_ENDOWMENT));
+	}
+
 	RuntimeGenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
@@ -139,20 +286,40 @@ fn testnet_genesis(
 			..Default::default()
 		},
 		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// Configure endowed accounts with their initial balance.
+			balances: endowed_accounts,
 		},
-		aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+		validator_set: ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
 		},
-		grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
-			..Default::default()
+		session: SessionConfig {
+			keys: initial_authorities
+				.iter()
+				.map(|x| (x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone())))
+				.collect(),
 		},
+		// Aura and GRANDPA authorities are set by `Session` from the session keys.
+		aura: AuraConfig { authorities: vec![] },
+		grandpa: GrandpaConfig { authorities: vec![], ..Default::default() },
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: root_key,
+		},
+		council: CouncilConfig { members: council, ..Default::default() },
+		technical_committee: TechnicalCommitteeConfig {
+			members: technical_committee,
+			..Default::default()
 		},
+		democracy: Default::default(),
 		transaction_payment: Default::default(),
+		assets: Default::default(),
+		treasury: Default::default(),
+		vesting: Default::default(),
+		// Lock part of the endowments of these accounts, as
+		// `(account, start, cliff, locked, per_block)`.
+		vesting_genesis: VestingGenesisConfig { vesting },
+		// Set identities as if the accounts called `set_identity`, reserving their deposits.
+		identity_genesis: IdentityGenesisConfig { identities },
+		template_module,
 	}
 }
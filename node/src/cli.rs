@@ -1,4 +1,6 @@
+use crate::service::Sealing;
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +9,18 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Replace Aura/GRANDPA with manual sealing for local development and testing.
+	///
+	/// `instant` seals a block as soon as a transaction enters the pool, `manual` only seals on
+	/// `engine_createBlock` and `<ms>` seals a block every given number of milliseconds. Blocks
+	/// can always be requested through the `engine_createBlock` RPC as well.
+	#[arg(long)]
+	pub sealing: Option<Sealing>,
+
+	/// Build the chain spec from a TOML or JSON network profile instead of `--chain`.
+	#[arg(long, global = true, value_name = "PATH")]
+	pub profile: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
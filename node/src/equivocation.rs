@@ -0,0 +1,113 @@
+//! Aura equivocation reporting.
+//!
+//! The Aura import queue only logs equivocations it finds in `check_for_equivocation`, so
+//! authorities watch imported blocks themselves and report slot authors that sealed two blocks
+//! for the same slot through the runtime's `AuraEquivocationApi`.
+
+use crate::service::FullClient;
+use futures::StreamExt;
+use node_template_runtime::opaque::{Block, Header};
+use pallet_validator_set::AuraEquivocationApi;
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{
+	sr25519::{AuthorityId as AuraId, AuthoritySignature as AuraSignature},
+	AuraApi, Slot, SlotDuration,
+};
+use sp_runtime::traits::Header as _;
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "aura-equivocation";
+
+/// Check every imported block for an Aura equivocation and report offenders on-chain.
+pub async fn report_aura_equivocations(
+	client: Arc<FullClient>,
+	offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+	slot_duration: SlotDuration,
+) {
+	let mut imported_blocks = client.import_notification_stream();
+
+	while let Some(notification) = imported_blocks.next().await {
+		if let Err(e) = check_and_report(
+			&client,
+			&offchain_tx_pool_factory,
+			slot_duration,
+			&notification.header,
+		) {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to check block {} for equivocations: {}",
+				notification.hash,
+				e
+			);
+		}
+	}
+}
+
+fn check_and_report(
+	client: &FullClient,
+	offchain_tx_pool_factory: &OffchainTransactionPoolFactory<Block>,
+	slot_duration: SlotDuration,
+	header: &Header,
+) -> Result<(), String> {
+	let slot = sc_consensus_aura::find_pre_digest::<Block, AuraSignature>(header)
+		.map_err(|e| e.to_string())?;
+
+	// The slot author, as the Aura verifier computes it.
+	let authorities: Vec<AuraId> = client
+		.runtime_api()
+		.authorities(*header.parent_hash())
+		.map_err(|e| e.to_string())?;
+	if authorities.is_empty() {
+		return Ok(())
+	}
+	let author = &authorities[(*slot % authorities.len() as u64) as usize];
+
+	let slot_now = Slot::from_timestamp(sp_timestamp::Timestamp::current(), slot_duration);
+	let Some(equivocation_proof) =
+		sc_consensus_slots::check_equivocation(client, slot_now, slot, header, author)
+			.map_err(|e| e.to_string())?
+	else {
+		return Ok(())
+	};
+
+	log::info!(
+		target: LOG_TARGET,
+		"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
+		author,
+		slot,
+		equivocation_proof.first_header.hash(),
+		equivocation_proof.second_header.hash(),
+	);
+
+	// Prove the offender's key ownership in the session the equivocating blocks were authored
+	// in, i.e. at their parent, rather than at the best block, which may be in a later session.
+	let mut runtime_api = client.runtime_api();
+	let Some(key_owner_proof) = runtime_api
+		.generate_key_ownership_proof(*header.parent_hash(), author.clone())
+		.map_err(|e| e.to_string())?
+	else {
+		log::debug!(
+			target: LOG_TARGET,
+			"Equivocation offender {:?} is not in the session of block {}",
+			author,
+			header.hash()
+		);
+		return Ok(())
+	};
+
+	let best_hash = client.info().best_hash;
+
+	runtime_api.register_extension(offchain_tx_pool_factory.offchain_transaction_pool(best_hash));
+	runtime_api
+		.submit_report_equivocation_unsigned_extrinsic(
+			best_hash,
+			equivocation_proof,
+			key_owner_proof,
+		)
+		.map_err(|e| e.to_string())?;
+
+	Ok(())
+}
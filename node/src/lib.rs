@@ -1,3 +1,5 @@
 pub mod chain_spec;
+pub mod equivocation;
+pub mod network_profile;
 pub mod rpc;
 pub mod service;
@@ -7,6 +7,8 @@ mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod equivocation;
+mod network_profile;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
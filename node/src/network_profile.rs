@@ -0,0 +1,397 @@
+//! Network profiles: a TOML or JSON description of a network's genesis parameters.
+//!
+//! A profile is turned into a [`ChainSpec`](crate::chain_spec::ChainSpec) by
+//! [`chain_spec::profile_config`](crate::chain_spec::profile_config), so new environments don't
+//! need their own copy of `chain_spec.rs`. Accounts and keys are given either as SS58 addresses
+//! or as secret URIs such as `//Alice`. Balances are integers or, for amounts TOML integers can't
+//! hold (above `i64::MAX`), decimal strings.
+//!
+//! ```toml
+//! name = "Staging"
+//! id = "staging"
+//! chain_type = "Live"
+//! token_symbol = "UNIT"
+//! token_decimals = 12
+//! council = ["//Alice", "//Bob", "//Charlie"]
+//! technical_committee = ["//Alice", "//Bob"]
+//!
+//! [[authorities]]
+//! seed = "//Alice"
+//!
+//! [[authorities]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! aura = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! grandpa = "5GoNkf6WdbxCFnPdAnYYQyCjAKPJgLNxXwPjwTh6DGg6gN3E"
+//!
+//! [[endowments]]
+//! account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! balance = "100000000000000000000"
+//!
+//! [[endowments]]
+//! account = "//Dave"
+//! balance = 1000000000000000
+//! vesting = { locked = 800000000000000, start = 100800, cliff = 201600, per_block = 1000000000 }
+//!
+//! [[identities]]
+//! account = "//Alice"
+//! display = "Alice"
+//! web = "https://alice.example"
+//! ```
+
+use crate::chain_spec;
+use node_template_runtime::{AccountId, Balance, BlockNumber, GenesisIdentity};
+use sc_service::{ChainType, Properties};
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_core::{crypto::Ss58Codec, ed25519, sr25519, Pair};
+use std::path::Path;
+
+/// Genesis parameters of a network, as read from a profile file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkProfile {
+	/// Human readable name of the chain.
+	pub name: String,
+	/// Identifier of the chain, also used as the name of its database directory.
+	pub id: String,
+	/// Type of the chain, `Live` unless specified.
+	#[serde(default = "default_chain_type")]
+	pub chain_type: ChainType,
+	/// Symbol of the native token shown by wallets, the runtime's `TOKEN_SYMBOL` if unset.
+	pub token_symbol: Option<String>,
+	/// Number of decimals of the native token, the runtime's `TOKEN_DECIMALS` if unset.
+	pub token_decimals: Option<u8>,
+	/// Account holding the sudo key. Only allowed for `Development` chains, others are governed
+	/// by the council and technical committee.
+	pub sudo: Option<String>,
+	/// Initial council members.
+	#[serde(default)]
+	pub council: Vec<String>,
+	/// Initial technical committee members.
+	#[serde(default)]
+	pub technical_committee: Vec<String>,
+	/// Initial PoA authorities.
+	pub authorities: Vec<Authority>,
+	/// Pre-funded accounts.
+	#[serde(default)]
+	pub endowments: Vec<Endowment>,
+	/// On-chain identities of e.g. the authorities and the sudo account. Each account must be
+	/// endowed with enough free balance for the identity deposit.
+	#[serde(default)]
+	pub identities: Vec<Identity>,
+}
+
+/// Validator account and session keys of an initial authority.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Authority {
+	/// Account and keys are all derived from the same secret URI, e.g. `//Alice`.
+	Seed { seed: String },
+	/// Aura (sr25519) and GRANDPA (ed25519) keys given separately. The validator account
+	/// defaults to the account of the Aura key.
+	Keys { account: Option<String>, aura: String, grandpa: String },
+}
+
+/// An account and its initial free balance.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowment {
+	/// The endowed account.
+	pub account: String,
+	/// Its initial free balance.
+	#[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+	pub balance: Balance,
+	/// Vesting schedule of part of the balance, which is liquid otherwise.
+	pub vesting: Option<Vesting>,
+}
+
+/// A linear vesting schedule of part of an endowment, optionally with a cliff.
+///
+/// Funds accrue `per_block` per block from `start` on. Nothing is unlocked before `cliff`, at
+/// which everything accrued since `start` is unlocked at once, and from which the rest keeps
+/// unlocking `per_block` per block.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+	/// The part of the balance locked at genesis.
+	#[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+	pub locked: Balance,
+	/// The block funds start to accrue at.
+	#[serde(default)]
+	pub start: BlockNumber,
+	/// The block nothing is unlocked before, at or after `start`. `start` unless specified.
+	pub cliff: Option<BlockNumber>,
+	/// The amount accrued per block from `start` on.
+	#[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+	pub per_block: Balance,
+}
+
+/// The on-chain identity of an account. Each field is at most 32 bytes long.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Identity {
+	/// The account the identity is set for.
+	pub account: String,
+	/// The name the account is displayed with.
+	pub display: Option<String>,
+	/// The full legal name.
+	pub legal: Option<String>,
+	/// A website.
+	pub web: Option<String>,
+	/// A Matrix handle.
+	pub riot: Option<String>,
+	/// An email address.
+	pub email: Option<String>,
+	/// A Twitter handle.
+	pub twitter: Option<String>,
+}
+
+fn default_chain_type() -> ChainType {
+	ChainType::Live
+}
+
+impl NetworkProfile {
+	/// Read a profile from a `.json` file, or from a TOML file for any other extension.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| format!("Error reading network profile {}: {}", path.display(), e))?;
+
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+			_ => toml::from_str(&content).map_err(|e| e.to_string()),
+		}
+		.map_err(|e| format!("Error parsing network profile {}: {}", path.display(), e))
+	}
+
+	/// The initial PoA authorities: validator accounts with their Aura and GRANDPA keys.
+	pub fn authority_keys(&self) -> Result<Vec<(AccountId, AuraId, GrandpaId)>, String> {
+		self.authorities
+			.iter()
+			.map(|authority| match authority {
+				Authority::Seed { seed } => Ok((
+					public_from_suri::<sr25519::Pair>(seed)?.into(),
+					public_from_suri::<sr25519::Pair>(seed)?.into(),
+					public_from_suri::<ed25519::Pair>(seed)?.into(),
+				)),
+				Authority::Keys { account, aura, grandpa } => {
+					let aura = parse_public::<sr25519::Pair>(aura)?;
+					let account = match account {
+						Some(account) => parse_account(account)?,
+						None => aura.into(),
+					};
+					Ok((account, aura.into(), parse_public::<ed25519::Pair>(grandpa)?.into()))
+				},
+			})
+			.collect()
+	}
+
+	/// The sudo account, if any.
+	pub fn sudo_account(&self) -> Result<Option<AccountId>, String> {
+		match &self.sudo {
+			Some(_) if self.chain_type != ChainType::Development => Err(format!(
+				"Network profile `{}` sets `sudo` but sudo is only available on Development chains",
+				self.id
+			)),
+			Some(sudo) => parse_account(sudo).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	/// The initial council members.
+	pub fn council_members(&self) -> Result<Vec<AccountId>, String> {
+		self.council.iter().map(|account| parse_account(account)).collect()
+	}
+
+	/// The initial technical committee members.
+	pub fn technical_committee_members(&self) -> Result<Vec<AccountId>, String> {
+		self.technical_committee.iter().map(|account| parse_account(account)).collect()
+	}
+
+	/// The pre-funded accounts and their balances.
+	pub fn endowed_accounts(&self) -> Result<Vec<(AccountId, Balance)>, String> {
+		self.endowments
+			.iter()
+			.map(|endowment| Ok((parse_account(&endowment.account)?, endowment.balance)))
+			.collect()
+	}
+
+	/// The genesis vesting schedules of the endowments, as `pallet_vesting_genesis` takes them:
+	/// `(account, start, cliff, locked, per_block)`.
+	pub fn vesting_schedules(
+		&self,
+	) -> Result<Vec<(AccountId, BlockNumber, BlockNumber, Balance, Balance)>, String> {
+		self.endowments
+			.iter()
+			.filter_map(|endowment| endowment.vesting.as_ref().map(|vesting| (endowment, vesting)))
+			.map(|(endowment, vesting)| {
+				if vesting.locked == 0 || vesting.per_block == 0 {
+					return Err(format!(
+						"Vesting schedule of {} must lock and unlock a non-zero amount",
+						endowment.account
+					))
+				}
+				if vesting.locked > endowment.balance {
+					return Err(format!(
+						"Vesting schedule of {} locks more than its balance",
+						endowment.account
+					))
+				}
+				let cliff = vesting.cliff.unwrap_or(vesting.start);
+				if cliff < vesting.start {
+					return Err(format!(
+						"Vesting schedule of {} has its cliff before its start",
+						endowment.account
+					))
+				}
+
+				Ok((
+					parse_account(&endowment.account)?,
+					vesting.start,
+					cliff,
+					vesting.locked,
+					vesting.per_block,
+				))
+			})
+			.collect()
+	}
+
+	/// The genesis identities.
+	pub fn genesis_identities(&self) -> Result<Vec<(AccountId, GenesisIdentity)>, String> {
+		self.identities
+			.iter()
+			.map(|identity| {
+				let field = |name: &str, value: &Option<String>| {
+					let value = value.clone().unwrap_or_default().into_bytes();
+					if value.len() > 32 {
+						return Err(format!(
+							"Identity of {} has a `{}` over 32 bytes",
+							identity.account, name
+						))
+					}
+					Ok(value)
+				};
+
+				Ok((
+					parse_account(&identity.account)?,
+					GenesisIdentity {
+						display: field("display", &identity.display)?,
+						legal: field("legal", &identity.legal)?,
+						web: field("web", &identity.web)?,
+						riot: field("riot", &identity.riot)?,
+						email: field("email", &identity.email)?,
+						twitter: field("twitter", &identity.twitter)?,
+					},
+				))
+			})
+			.collect()
+	}
+
+	/// The chain spec properties wallets use to display balances and addresses.
+	///
+	/// The SS58 format is always the runtime's `SS58_PREFIX`, which addresses are encoded with.
+	pub fn properties(&self) -> Properties {
+		let mut properties = chain_spec::chain_properties();
+		if let Some(token_symbol) = &self.token_symbol {
+			properties.insert("tokenSymbol".into(), token_symbol.clone().into());
+		}
+		if let Some(token_decimals) = self.token_decimals {
+			properties.insert("tokenDecimals".into(), token_decimals.into());
+		}
+		properties
+	}
+}
+
+/// Parse an account from an SS58 address or a secret URI.
+fn parse_account(s: &str) -> Result<AccountId, String> {
+	Ok(parse_public::<sr25519::Pair>(s)?.into())
+}
+
+/// Parse a public key from an SS58 address or a secret URI.
+fn parse_public<TPair: Pair>(s: &str) -> Result<TPair::Public, String>
+where
+	TPair::Public: Ss58Codec,
+{
+	match TPair::Public::from_ss58check_with_version(s) {
+		Ok((public, _)) => Ok(public),
+		Err(_) => public_from_suri::<TPair>(s),
+	}
+}
+
+fn public_from_suri<TPair: Pair>(suri: &str) -> Result<TPair::Public, String> {
+	TPair::from_string(suri, None)
+		.map(|pair| pair.public())
+		.map_err(|e| format!("`{}` is neither a valid SS58 address nor secret URI: {:?}", suri, e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PROFILE: &str = r#"
+		name = "Test"
+		id = "test"
+
+		[[authorities]]
+		seed = "//Alice"
+
+		[[endowments]]
+		account = "//Alice"
+		balance = 1000000000000000
+
+		[[endowments]]
+		account = "//Bob"
+		balance = "100000000000000000000"
+		vesting = { locked = "10000000000000000000", per_block = 1000000000 }
+	"#;
+
+	#[test]
+	fn balances_can_be_integers_or_strings() {
+		let profile: NetworkProfile = toml::from_str(PROFILE).unwrap();
+		let endowments = profile.endowed_accounts().unwrap();
+
+		assert_eq!(endowments[0].1, 1_000_000_000_000_000);
+		// Above `i64::MAX`, the largest TOML integer.
+		assert_eq!(endowments[1].1, 100_000_000_000_000_000_000);
+		assert!(endowments[1].1 > i64::MAX as Balance);
+		let vesting = profile.endowments[1].vesting.as_ref().unwrap();
+		assert_eq!(vesting.locked, 10_000_000_000_000_000_000);
+		assert_eq!(vesting.per_block, 1_000_000_000);
+	}
+
+	#[test]
+	fn invalid_balances_are_rejected() {
+		let profile = PROFILE.replace("\"100000000000000000000\"", "\"100 units\"");
+		assert!(toml::from_str::<NetworkProfile>(&profile).is_err());
+
+		let profile = PROFILE.replace("balance = 1000000000000000", "balance = -1");
+		assert!(toml::from_str::<NetworkProfile>(&profile).is_err());
+	}
+
+	#[test]
+	fn vesting_cliffs_default_to_the_start() {
+		let profile = PROFILE.replace("per_block =", "start = 10, per_block =");
+		let profile: NetworkProfile = toml::from_str(&profile).unwrap();
+		let schedules = profile.vesting_schedules().unwrap();
+		assert_eq!(schedules.len(), 1);
+		assert_eq!(
+			(schedules[0].1, schedules[0].2, schedules[0].3, schedules[0].4),
+			(10, 10, 10_000_000_000_000_000_000, 1_000_000_000)
+		);
+
+		let profile = PROFILE.replace("per_block =", "start = 10, cliff = 20, per_block =");
+		let profile: NetworkProfile = toml::from_str(&profile).unwrap();
+		let schedules = profile.vesting_schedules().unwrap();
+		assert_eq!((schedules[0].1, schedules[0].2), (10, 20));
+	}
+
+	#[test]
+	fn vesting_cliffs_before_the_start_are_rejected() {
+		let profile = PROFILE.replace("per_block =", "start = 10, cliff = 5, per_block =");
+		let profile: NetworkProfile = toml::from_str(&profile).unwrap();
+		assert!(profile.vesting_schedules().is_err());
+	}
+}
@@ -0,0 +1,501 @@
+//! `contracts_*` RPC methods, dry-running contract calls, instantiations and code uploads
+//! through the runtime's `ContractsApi`.
+//!
+//! Substrate dropped `pallet-contracts-rpc` in favour of calling `ContractsApi` via `state_call`;
+//! these methods keep a JSON interface for tools that don't decode SCALE themselves.
+//!
+//! Dry runs default to the maximum block weight as gas limit and collect debug output, so they
+//! are unsafe: they are only served along with the other unsafe methods. Storage queries are
+//! always served.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, EventRecord, Hash, Weight,
+};
+use pallet_contracts::{ContractsApi as ContractsRuntimeApi, Determinism};
+use pallet_contracts_primitives::{
+	Code, CodeUploadResult, ContractExecResult, ContractInstantiateResult, ExecReturnValue,
+	StorageDeposit,
+};
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::DispatchError;
+
+/// A dry-run contract call.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CallRequest {
+	origin: AccountId,
+	dest: AccountId,
+	value: NumberOrHex,
+	/// The maximum block weight if unset.
+	gas_limit: Option<Weight>,
+	storage_deposit_limit: Option<NumberOrHex>,
+	input_data: Bytes,
+}
+
+/// The code of a contract to instantiate.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum InstantiateCode {
+	/// Wasm code to upload along.
+	Upload(Bytes),
+	/// The hash of already uploaded code.
+	Existing(Hash),
+}
+
+/// A dry-run contract instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InstantiateRequest {
+	origin: AccountId,
+	value: NumberOrHex,
+	/// The maximum block weight if unset.
+	gas_limit: Option<Weight>,
+	storage_deposit_limit: Option<NumberOrHex>,
+	code: InstantiateCode,
+	data: Bytes,
+	salt: Bytes,
+}
+
+/// A dry-run code upload.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CodeUploadRequest {
+	origin: AccountId,
+	code: Bytes,
+	storage_deposit_limit: Option<NumberOrHex>,
+	/// Whether the code may use non-deterministic instructions, `false` unless given.
+	#[serde(default)]
+	relaxed: bool,
+}
+
+/// The storage deposit charged, or refunded, by a dry run.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Deposit {
+	/// Deposit refunded to the origin.
+	Refund(NumberOrHex),
+	/// Deposit charged from the origin.
+	Charge(NumberOrHex),
+}
+
+/// The output of a contract execution.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnValue {
+	/// The `ReturnFlags` bits, e.g. 1 if the contract reverted.
+	flags: u32,
+	data: Bytes,
+}
+
+/// The result of an instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Instantiated {
+	result: ReturnValue,
+	account_id: AccountId,
+}
+
+/// The result of a code upload.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Uploaded {
+	code_hash: Hash,
+	deposit: NumberOrHex,
+}
+
+/// The result of a dry run and its costs. `result` holds the output, or the error if the
+/// execution failed.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRun<R> {
+	gas_consumed: Weight,
+	gas_required: Weight,
+	storage_deposit: Deposit,
+	debug_message: String,
+	result: Result<R, String>,
+}
+
+/// Dry runs of contract executions, and contract storage queries.
+#[rpc(server)]
+pub trait ContractsApi<BlockHash> {
+	/// Dry-run a call to a contract at the given block, or at the best block if none is given.
+	/// Unsafe.
+	#[method(name = "contracts_call")]
+	fn call(
+		&self,
+		call_request: CallRequest,
+		at: Option<BlockHash>,
+	) -> RpcResult<DryRun<ReturnValue>>;
+
+	/// Dry-run the instantiation of a contract. Unsafe.
+	#[method(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest,
+		at: Option<BlockHash>,
+	) -> RpcResult<DryRun<Instantiated>>;
+
+	/// Dry-run the upload of contract code. Unsafe.
+	#[method(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest,
+		at: Option<BlockHash>,
+	) -> RpcResult<Result<Uploaded, String>>;
+
+	/// Get the value stored under `key` by the contract at `address`, if any.
+	#[method(name = "contracts_getStorage")]
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Bytes>>;
+}
+
+/// Provides RPC methods to dry-run contract executions.
+pub struct Contracts<C> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	/// Whether to deny unsafe calls.
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> Contracts<C> {
+	/// Creates a new instance of the contracts RPC helper.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { client, deny_unsafe }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// A balance doesn't fit a `Balance`.
+	InvalidBalance,
+	/// The contract doesn't exist.
+	ContractNotFound,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidBalance => 2,
+			Error::ContractNotFound => 3,
+		}
+	}
+}
+
+impl<C> ContractsApiServer<Hash> for Contracts<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash, EventRecord>,
+{
+	fn call(&self, call_request: CallRequest, at: Option<Hash>) -> RpcResult<DryRun<ReturnValue>> {
+		self.deny_unsafe.check_if_safe()?;
+		let CallRequest { origin, dest, value, gas_limit, storage_deposit_limit, input_data } =
+			call_request;
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let result: ContractExecResult<Balance, EventRecord> = self
+			.client
+			.runtime_api()
+			.call(
+				at,
+				origin,
+				dest,
+				balance(value)?,
+				gas_limit,
+				storage_deposit_limit.map(balance).transpose()?,
+				input_data.to_vec(),
+			)
+			.map_err(runtime_error)?;
+
+		Ok(dry_run(
+			result.gas_consumed,
+			result.gas_required,
+			result.storage_deposit,
+			result.debug_message,
+			result.result.map(return_value),
+		))
+	}
+
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest,
+		at: Option<Hash>,
+	) -> RpcResult<DryRun<Instantiated>> {
+		self.deny_unsafe.check_if_safe()?;
+		let InstantiateRequest {
+			origin,
+			value,
+			gas_limit,
+			storage_deposit_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let code = match code {
+			InstantiateCode::Upload(code) => Code::Upload(code.to_vec()),
+			InstantiateCode::Existing(code_hash) => Code::Existing(code_hash),
+		};
+
+		let result: ContractInstantiateResult<AccountId, Balance, EventRecord> = self
+			.client
+			.runtime_api()
+			.instantiate(
+				at,
+				origin,
+				balance(value)?,
+				gas_limit,
+				storage_deposit_limit.map(balance).transpose()?,
+				code,
+				data.to_vec(),
+				salt.to_vec(),
+			)
+			.map_err(runtime_error)?;
+
+		Ok(dry_run(
+			result.gas_consumed,
+			result.gas_required,
+			result.storage_deposit,
+			result.debug_message,
+			result.result.map(|instantiated| Instantiated {
+				result: return_value(instantiated.result),
+				account_id: instantiated.account_id,
+			}),
+		))
+	}
+
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest,
+		at: Option<Hash>,
+	) -> RpcResult<Result<Uploaded, String>> {
+		self.deny_unsafe.check_if_safe()?;
+		let CodeUploadRequest { origin, code, storage_deposit_limit, relaxed } = upload_request;
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let determinism = if relaxed { Determinism::Relaxed } else { Determinism::Enforced };
+
+		let result: CodeUploadResult<Hash, Balance> = self
+			.client
+			.runtime_api()
+			.upload_code(
+				at,
+				origin,
+				code.to_vec(),
+				storage_deposit_limit.map(balance).transpose()?,
+				determinism,
+			)
+			.map_err(runtime_error)?;
+
+		Ok(result
+			.map(|uploaded| Uploaded {
+				code_hash: uploaded.code_hash,
+				deposit: uploaded.deposit.into(),
+			})
+			.map_err(dispatch_error))
+	}
+
+	fn get_storage(
+		&self,
+		address: AccountId,
+		key: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<Option<Bytes>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let result = self
+			.client
+			.runtime_api()
+			.get_storage(at, address, key.to_vec())
+			.map_err(runtime_error)?
+			.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::ContractNotFound.into(),
+					"The contract doesn't exist.",
+					Some(format!("{:?}", e)),
+				))
+			})?;
+
+		Ok(result.map(Bytes))
+	}
+}
+
+fn dry_run<R>(
+	gas_consumed: Weight,
+	gas_required: Weight,
+	storage_deposit: StorageDeposit<Balance>,
+	debug_message: Vec<u8>,
+	result: Result<R, DispatchError>,
+) -> DryRun<R> {
+	DryRun {
+		gas_consumed,
+		gas_required,
+		storage_deposit: match storage_deposit {
+			StorageDeposit::Refund(amount) => Deposit::Refund(amount.into()),
+			StorageDeposit::Charge(amount) => Deposit::Charge(amount.into()),
+		},
+		debug_message: String::from_utf8_lossy(&debug_message).into_owned(),
+		result: result.map_err(dispatch_error),
+	}
+}
+
+fn return_value(value: ExecReturnValue) -> ReturnValue {
+	ReturnValue { flags: value.flags.bits(), data: value.data.into() }
+}
+
+fn dispatch_error(e: DispatchError) -> String {
+	format!("{:?}", e)
+}
+
+fn balance(value: NumberOrHex) -> Result<Balance, CallError> {
+	let description = format!("{:?}", value);
+	value.try_into().map_err(|_| {
+		CallError::Custom(ErrorObject::owned(
+			Error::InvalidBalance.into(),
+			"Balance out of range.",
+			Some(description),
+		))
+	})
+}
+
+fn runtime_error(e: sp_api::ApiError) -> CallError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to dry-run the contract execution.",
+		Some(e.to_string()),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::de::DeserializeOwned;
+	use serde_json::json;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	// Deserialize `json`, then check it serializes back unchanged.
+	fn round_trip<T: Serialize + DeserializeOwned>(json: serde_json::Value) -> T {
+		let value: T = serde_json::from_value(json.clone()).unwrap();
+		assert_eq!(serde_json::to_value(&value).unwrap(), json);
+		value
+	}
+
+	fn weight(ref_time: u64) -> serde_json::Value {
+		serde_json::to_value(Weight::from_parts(ref_time, 0)).unwrap()
+	}
+
+	#[test]
+	fn call_requests_round_trip() {
+		let request: CallRequest = round_trip(json!({
+			"origin": ALICE,
+			"dest": ALICE,
+			"value": "0x10",
+			"gasLimit": weight(1_000),
+			"storageDepositLimit": 100,
+			"inputData": "0x0102",
+		}));
+		assert_eq!(balance(request.value).ok(), Some(16));
+		assert_eq!(request.gas_limit, Some(Weight::from_parts(1_000, 0)));
+		assert_eq!(request.input_data.to_vec(), vec![1, 2]);
+
+		round_trip::<CallRequest>(json!({
+			"origin": ALICE,
+			"dest": ALICE,
+			"value": 0,
+			"gasLimit": null,
+			"storageDepositLimit": null,
+			"inputData": "0x",
+		}));
+	}
+
+	#[test]
+	fn requests_reject_unknown_fields() {
+		let request = json!({
+			"origin": ALICE,
+			"code": "0x00",
+			"storageDepositLimit": null,
+			"relaxed": false,
+			"determinism": "Relaxed",
+		});
+		assert!(serde_json::from_value::<CodeUploadRequest>(request).is_err());
+	}
+
+	#[test]
+	fn instantiate_requests_round_trip() {
+		for code in [json!({ "upload": "0x0061736d" }), json!({ "existing": Hash::repeat_byte(1) })]
+		{
+			round_trip::<InstantiateRequest>(json!({
+				"origin": ALICE,
+				"value": 0,
+				"gasLimit": null,
+				"storageDepositLimit": null,
+				"code": code,
+				"data": "0x",
+				"salt": "0x01",
+			}));
+		}
+	}
+
+	#[test]
+	fn code_upload_requests_round_trip() {
+		let request: CodeUploadRequest = round_trip(json!({
+			"origin": ALICE,
+			"code": "0x0061736d",
+			"storageDepositLimit": "0x100",
+			"relaxed": true,
+		}));
+		assert!(request.relaxed);
+
+		// `relaxed` is optional.
+		let request: CodeUploadRequest = serde_json::from_value(json!({
+			"origin": ALICE,
+			"code": "0x0061736d",
+			"storageDepositLimit": null,
+		}))
+		.unwrap();
+		assert!(!request.relaxed);
+	}
+
+	#[test]
+	fn dry_run_results_round_trip() {
+		round_trip::<DryRun<Instantiated>>(json!({
+			"gasConsumed": weight(1_000),
+			"gasRequired": weight(2_000),
+			"storageDeposit": { "charge": 10 },
+			"debugMessage": "",
+			"result": { "Ok": {
+				"result": { "flags": 0, "data": "0x2a" },
+				"accountId": ALICE,
+			} },
+		}));
+		round_trip::<DryRun<ReturnValue>>(json!({
+			"gasConsumed": weight(1_000),
+			"gasRequired": weight(1_000),
+			"storageDeposit": { "refund": "0x10000000000000000" },
+			"debugMessage": "panicked",
+			"result": { "Err": "Module(ModuleError { .. })" },
+		}));
+		round_trip::<Result<Uploaded, String>>(json!({ "Ok": {
+			"codeHash": Hash::repeat_byte(1),
+			"deposit": 10,
+		} }));
+	}
+}
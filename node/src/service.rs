@@ -1,16 +1,22 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use futures::FutureExt;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{Backend, BlockBackend};
+use futures::{channel::mpsc, FutureExt, StreamExt};
+use node_template_runtime::{
+	self, opaque::Block, Hash, Runtime, RuntimeApi, StorageValue, SLOT_DURATION,
+};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sp_core::{storage::StorageKey, Decode};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -32,6 +38,36 @@ impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
 	}
 }
 
+/// How blocks are authored when the node runs with `--sealing`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction is imported into the pool.
+	Instant,
+	/// Only seal blocks when requested through `engine_createBlock`.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			millis => match millis.parse::<u64>() {
+				Ok(0) => Err("sealing interval must be greater than zero".into()),
+				Ok(millis) => Ok(Sealing::Interval(millis)),
+				Err(_) => Err(format!(
+					"invalid sealing `{}`, expected `instant`, `manual` or a number of milliseconds",
+					s
+				)),
+			},
+		}
+	}
+}
+
 pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
@@ -40,6 +76,7 @@ type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 #[allow(clippy::type_complexity)]
 pub fn new_partial(
 	config: &Configuration,
+	sealing: Option<Sealing>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -102,6 +139,26 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	// Manually sealed blocks carry no Aura seal, so they can't go through the Aura verifier.
+	if sealing.is_some() {
+		let import_queue = sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		);
+
+		return Ok(sc_service::PartialComponents {
+			client,
+			backend,
+			task_manager,
+			import_queue,
+			keystore_container,
+			select_chain,
+			transaction_pool,
+			other: (grandpa_block_import, grandpa_link, telemetry),
+		})
+	}
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
 	let import_queue =
@@ -140,7 +197,12 @@ pub fn new_partial(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// When `sealing` is set, Aura and GRANDPA are replaced by a manual-seal authorship task.
+pub fn new_full(
+	config: Configuration,
+	sealing: Option<Sealing>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -150,7 +212,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial(&config, sealing)?;
 
 	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
 
@@ -208,13 +270,27 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Channel for the `engine_*` RPCs to drive the manual-seal authorship task.
+	let (command_sink, commands_stream) = match sealing {
+		Some(_) => {
+			let (sink, stream) = mpsc::channel(1024);
+			(Some(sink), Some(stream))
+		},
+		None => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+		Box::new(move |deny_unsafe, subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				subscription_executor,
+				command_sink: command_sink.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -234,6 +310,81 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if let (Some(sealing), Some(commands_stream)) = (sealing, commands_stream) {
+		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		let seal_new_block = |create_empty| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		};
+		let commands_stream: Box<dyn futures::Stream<Item = EngineCommand<Hash>> + Send + Unpin> =
+			match sealing {
+				Sealing::Manual => Box::new(commands_stream),
+				Sealing::Instant => Box::new(futures::stream::select(
+					commands_stream,
+					transaction_pool
+						.import_notification_stream()
+						.map(move |_| seal_new_block(false)),
+				)),
+				Sealing::Interval(millis) => Box::new(futures::stream::select(
+					commands_stream,
+					futures::stream::unfold((), move |()| async move {
+						futures_timer::Delay::new(Duration::from_millis(millis)).await;
+						Some((seal_new_block(true), ()))
+					})
+					.boxed(),
+				)),
+			};
+
+		// Sealed blocks may come faster than `SLOT_DURATION`, so the timestamp is bumped to at
+		// least one slot past the parent's to keep the Aura slot and `pallet_timestamp` happy.
+		// The parent's `pallet_timestamp::Now` is read from its state, so this also holds after
+		// a restart.
+		let timestamp_client = client.clone();
+		let timestamp_now_key = StorageKey(pallet_timestamp::Now::<Runtime>::hashed_key().to_vec());
+
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env: proposer_factory,
+			client: client.clone(),
+			pool: transaction_pool,
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+			create_inherent_data_providers: move |parent_hash, ()| {
+				// `Now` is unset in the genesis block, whose children may use any timestamp.
+				let parent_timestamp = timestamp_client
+					.storage(parent_hash, &timestamp_now_key)
+					.map(|now| now.and_then(|now| u64::decode(&mut &now.0[..]).ok()).unwrap_or(0));
+				async move {
+					let now = *sp_timestamp::Timestamp::current();
+					let timestamp = now.max(parent_timestamp? + SLOT_DURATION);
+
+					Ok(sp_timestamp::InherentDataProvider::new(timestamp.into()))
+				}
+			},
+		});
+
+		// the manual-seal authoring task is considered essential, i.e. if it
+		// fails we take down the service with it.
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"manual-seal",
+			Some("block-authoring"),
+			manual_seal,
+		);
+
+		network_starter.start_network();
+		return Ok(task_manager)
+	}
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -245,6 +396,17 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 
 		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
+		// Turn Aura equivocations seen on import into on-chain offences.
+		task_manager.spawn_handle().spawn(
+			"aura-equivocation-reporter",
+			None,
+			crate::equivocation::report_aura_equivocations(
+				client.clone(),
+				OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+				slot_duration,
+			),
+		);
+
 		let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _>(
 			StartAuraParams {
 				slot_duration,
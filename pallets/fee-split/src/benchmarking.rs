@@ -0,0 +1,28 @@
+//! Benchmarking setup for pallet-fee-split
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as FeeSplit;
+use frame_benchmarking::v2::*;
+use frame_support::traits::EnsureOrigin;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_author_shares() -> Result<(), BenchmarkError> {
+		let origin =
+			T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let shares =
+			AuthorShares { fees: Percent::from_percent(50), tips: Percent::from_percent(50) };
+		#[extrinsic_call]
+		set_author_shares(origin as T::RuntimeOrigin, shares);
+
+		assert_eq!(Shares::<T>::get(), shares);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(FeeSplit, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,208 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Fee Split Pallet
+//!
+//! Distributes transaction fees and tips between the author of the current block and a treasury
+//! account instead of burning them. The author's shares are set by `UpdateOrigin` and the
+//! treasury gets the rest, or everything when the block author can't be found.
+//!
+//! The pallet is the `OnUnbalanced` handler to use in `pallet_transaction_payment`'s
+//! `CurrencyAdapter`, and [`AssetFeeSplit`] the credit handler to use in
+//! `pallet_asset_tx_payment`'s `FungiblesAdapter` for fees paid in assets. [`DustToTreasury`]
+//! can be used as `pallet_balances`' `DustRemoval` to have dust deposited into the treasury as
+//! well.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::{
+	fungible, fungibles, Currency, FindAuthor, Get, Imbalance, OnUnbalanced,
+};
+use pallet_asset_tx_payment::HandleCredit;
+use scale_info::TypeInfo;
+use sp_runtime::{Percent, RuntimeDebug};
+use sp_std::marker::PhantomData;
+
+/// The balance type of the fee currency.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+/// The imbalance type of withdrawn fees.
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+/// The balance type of dust credits.
+pub type DustBalanceOf<T> =
+	<<T as Config>::Currency as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The block author's shares of transaction fees and tips. The treasury gets the rest.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct AuthorShares {
+	/// Share of the fee charged for the weight and length of a transaction.
+	pub fees: Percent,
+	/// Share of the tip.
+	pub tips: Percent,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The currency fees are paid in.
+		type Currency: Currency<Self::AccountId> + fungible::Balanced<Self::AccountId>;
+		/// Finds the author of the current block from the pre-runtime digests, e.g. Aura's.
+		type FindAuthor: FindAuthor<Self::AccountId>;
+		/// The account receiving the fees not given to the block author.
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+		/// The author shares used until `set_author_shares` is called.
+		#[pallet::constant]
+		type DefaultAuthorShares: Get<AuthorShares>;
+		/// Origin allowed to change the author shares.
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Type representing the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The block author's shares of fees and tips.
+	#[pallet::storage]
+	#[pallet::getter(fn author_shares)]
+	pub type Shares<T: Config> = StorageValue<_, AuthorShares, ValueQuery, T::DefaultAuthorShares>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The block author's shares were changed. [shares]
+		AuthorSharesSet { shares: AuthorShares },
+		/// The fees and tip of a transaction were distributed. [author, to_author, to_treasury]
+		FeesDistributed {
+			author: Option<T::AccountId>,
+			to_author: BalanceOf<T>,
+			to_treasury: BalanceOf<T>,
+		},
+		/// Dust of a reaped account was deposited into the treasury. [amount]
+		DustDeposited { amount: DustBalanceOf<T> },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the block author's shares of fees and tips.
+		///
+		/// The origin for this call must be `UpdateOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_author_shares())]
+		pub fn set_author_shares(origin: OriginFor<T>, shares: AuthorShares) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			<Shares<T>>::put(shares);
+			Self::deposit_event(Event::AuthorSharesSet { shares });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The author of the current block, if it can be found.
+		pub fn author() -> Option<T::AccountId> {
+			let digest = <frame_system::Pallet<T>>::digest();
+			let pre_runtime_digests = digest.logs().iter().filter_map(|d| d.as_pre_runtime());
+			T::FindAuthor::find_author(pre_runtime_digests)
+		}
+	}
+}
+
+/// Splits the fees, then the tip, of a transaction between the block author and the treasury.
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for Pallet<T> {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalanceOf<T>>) {
+		let Some(fees) = fees_then_tips.next() else { return };
+		let shares = <Shares<T>>::get();
+
+		let amount = fees.peek();
+		let (mut to_author, mut to_treasury) = fees.split(shares.fees.mul_floor(amount));
+		if let Some(tips) = fees_then_tips.next() {
+			let amount = tips.peek();
+			let (tips_to_author, tips_to_treasury) = tips.split(shares.tips.mul_floor(amount));
+			to_author.subsume(tips_to_author);
+			to_treasury.subsume(tips_to_treasury);
+		}
+
+		let author = Self::author();
+		if author.is_none() {
+			to_treasury.subsume(to_author);
+			to_author = NegativeImbalanceOf::<T>::zero();
+		}
+
+		let (author_amount, treasury_amount) = (to_author.peek(), to_treasury.peek());
+		if let Some(author) = &author {
+			<T::Currency as Currency<_>>::resolve_creating(author, to_author);
+		}
+		<T::Currency as Currency<_>>::resolve_creating(&T::TreasuryAccount::get(), to_treasury);
+
+		Self::deposit_event(Event::FeesDistributed {
+			author,
+			to_author: author_amount,
+			to_treasury: treasury_amount,
+		});
+	}
+}
+
+/// Splits transaction fees paid in an asset between the block author and the treasury, by the
+/// author's fee share as the credit includes the tip.
+///
+/// A share the author can't receive, e.g. because it's below the asset's `min_balance` and the
+/// author holds none of the asset, goes to the treasury. What the treasury can't receive either
+/// is burned.
+pub struct AssetFeeSplit<T>(PhantomData<T>);
+
+impl<T, F> HandleCredit<T::AccountId, F> for AssetFeeSplit<T>
+where
+	T: Config,
+	F: fungibles::Balanced<T::AccountId>,
+{
+	fn handle_credit(credit: fungibles::Credit<T::AccountId, F>) {
+		let amount = credit.peek();
+		let (to_author, mut to_treasury) = credit.split(<Shares<T>>::get().fees.mul_floor(amount));
+
+		let unclaimed = match Pallet::<T>::author() {
+			Some(author) => F::resolve(&author, to_author).err(),
+			None => Some(to_author),
+		};
+		if let Some(unclaimed) = unclaimed {
+			// Both parts are of the same asset, so this can't fail.
+			let _ = to_treasury.subsume(unclaimed);
+		}
+		let _ = F::resolve(&T::TreasuryAccount::get(), to_treasury);
+	}
+}
+
+/// Deposits the dust of reaped accounts into the treasury account. Dust the treasury account
+/// can't receive, because it doesn't exist and the dust is below the existential deposit, is
+/// burned.
+pub struct DustToTreasury<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<fungible::Credit<T::AccountId, T::Currency>> for DustToTreasury<T> {
+	fn on_nonzero_unbalanced(dust: fungible::Credit<T::AccountId, T::Currency>) {
+		let amount = dust.peek();
+		let treasury = T::TreasuryAccount::get();
+		if <T::Currency as fungible::Balanced<_>>::resolve(&treasury, dust).is_ok() {
+			Pallet::<T>::deposit_event(Event::DustDeposited { amount });
+		}
+	}
+}
@@ -0,0 +1,121 @@
+use crate::{mock::*, AssetFeeSplit, AuthorShares, Event};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{fungibles::Balanced, Currency, OnUnbalanced},
+};
+use pallet_asset_tx_payment::HandleCredit;
+use sp_runtime::{DispatchError, Percent};
+
+fn distribute(fee: u128, tip: u128) {
+	FeeSplit::on_unbalanceds(vec![Balances::issue(fee), Balances::issue(tip)].into_iter());
+}
+
+#[test]
+fn fees_are_split_and_tips_go_to_the_author() {
+	new_test_ext().execute_with(|| {
+		distribute(100, 30);
+
+		// 20% of the fee and the whole tip to the author, the rest to the treasury.
+		assert_eq!(Balances::free_balance(1), 150);
+		assert_eq!(Balances::free_balance(TREASURY), 180);
+		System::assert_last_event(
+			Event::FeesDistributed { author: Some(1), to_author: 50, to_treasury: 80 }.into(),
+		);
+	});
+}
+
+#[test]
+fn fees_go_to_the_treasury_without_author() {
+	new_test_ext().execute_with(|| {
+		set_author(None);
+		distribute(100, 30);
+
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Balances::free_balance(TREASURY), 230);
+		System::assert_last_event(
+			Event::FeesDistributed { author: None, to_author: 0, to_treasury: 130 }.into(),
+		);
+	});
+}
+
+#[test]
+fn set_author_shares_changes_the_split() {
+	new_test_ext().execute_with(|| {
+		let shares =
+			AuthorShares { fees: Percent::from_percent(50), tips: Percent::from_percent(0) };
+		assert_noop!(
+			FeeSplit::set_author_shares(RuntimeOrigin::signed(1), shares),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(FeeSplit::set_author_shares(RuntimeOrigin::root(), shares));
+		System::assert_last_event(Event::AuthorSharesSet { shares }.into());
+		assert_eq!(FeeSplit::author_shares(), shares);
+
+		distribute(100, 30);
+		assert_eq!(Balances::free_balance(1), 150);
+		assert_eq!(Balances::free_balance(TREASURY), 180);
+	});
+}
+
+#[test]
+fn dust_goes_to_the_treasury() {
+	new_test_ext().execute_with(|| {
+		// Leaves 5, below the existential deposit of 10, in account 1.
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 95));
+
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(TREASURY), 105);
+		System::assert_has_event(Event::DustDeposited { amount: 5 }.into());
+	});
+}
+
+const ASSET: u32 = 7;
+
+/// Create a sufficient `ASSET` with `min_balance` and distribute an asset fee of `fee`.
+fn distribute_asset_fee(min_balance: u128, fee: u128) {
+	assert_ok!(Assets::force_create(RuntimeOrigin::root(), ASSET, 2, true, min_balance));
+	AssetFeeSplit::<Test>::handle_credit(Assets::issue(ASSET, fee));
+}
+
+#[test]
+fn asset_fees_are_split() {
+	new_test_ext().execute_with(|| {
+		distribute_asset_fee(1, 100);
+
+		// 20% to the author, the rest to the treasury.
+		assert_eq!(Assets::balance(ASSET, 1), 20);
+		assert_eq!(Assets::balance(ASSET, TREASURY), 80);
+		assert_eq!(Assets::total_supply(ASSET), 100);
+	});
+}
+
+#[test]
+fn asset_fees_go_to_the_treasury_without_author() {
+	new_test_ext().execute_with(|| {
+		set_author(None);
+		distribute_asset_fee(1, 100);
+
+		assert_eq!(Assets::balance(ASSET, TREASURY), 100);
+	});
+}
+
+#[test]
+fn asset_fees_the_author_cannot_receive_go_to_the_treasury() {
+	new_test_ext().execute_with(|| {
+		// The author's 20 is below the minimum balance of 50.
+		distribute_asset_fee(50, 100);
+
+		assert_eq!(Assets::balance(ASSET, 1), 0);
+		assert_eq!(Assets::balance(ASSET, TREASURY), 100);
+	});
+}
+
+#[test]
+fn asset_fees_nobody_can_receive_are_burned() {
+	new_test_ext().execute_with(|| {
+		distribute_asset_fee(200, 100);
+
+		assert_eq!(Assets::balance(ASSET, TREASURY), 0);
+		assert_eq!(Assets::total_supply(ASSET), 0);
+	});
+}
@@ -0,0 +1,39 @@
+//! Placeholder weights for pallet_fee_split.
+//!
+//! Not benchmark output: `set_author_shares` only overwrites the two-byte `Shares` value without
+//! reading any storage, so it has no proof size and a generous 15 µs of ref time, about twice
+//! what FRAME's benchmarks give calls that just put a small value. Its single database write is
+//! counted from the code.
+//!
+//! TODO: Regenerate with `benchmark pallet --pallet pallet_fee_split` on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_fee_split.
+pub trait WeightInfo {
+	fn set_author_shares() -> Weight;
+}
+
+/// Weights for pallet_fee_split using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: FeeSplit Shares (r:0 w:1)
+	fn set_author_shares() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: FeeSplit Shares (r:0 w:1)
+	fn set_author_shares() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
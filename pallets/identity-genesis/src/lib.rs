@@ -0,0 +1,102 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Identity Genesis Pallet
+//!
+//! Sets `pallet_identity` identities at genesis, which `pallet_identity` can't do itself, so
+//! e.g. the initial validators of a network ship with identities.
+//!
+//! Identities are set as if each account called `set_identity`, so the identity deposit is
+//! reserved from its genesis balance and building the genesis fails if it can't be afforded.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::Get;
+use pallet_identity::{Data, IdentityInfo};
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::{boxed::Box, prelude::*};
+
+/// The fields of an identity set at genesis. Empty fields are left unset.
+#[derive(Clone, PartialEq, Eq, Default, RuntimeDebug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenesisIdentity {
+	/// A name the account is displayed with.
+	pub display: Vec<u8>,
+	/// The full legal name.
+	pub legal: Vec<u8>,
+	/// A website.
+	pub web: Vec<u8>,
+	/// A Matrix handle.
+	pub riot: Vec<u8>,
+	/// An email address.
+	pub email: Vec<u8>,
+	/// A Twitter handle.
+	pub twitter: Vec<u8>,
+}
+
+impl GenesisIdentity {
+	/// The identity as `set_identity` takes it, or the name of a field longer than the 32 bytes
+	/// of a raw `Data`.
+	pub fn into_info<FieldLimit: Get<u32>>(self) -> Result<IdentityInfo<FieldLimit>, &'static str> {
+		Ok(IdentityInfo {
+			additional: Default::default(),
+			display: data("display", self.display)?,
+			legal: data("legal", self.legal)?,
+			web: data("web", self.web)?,
+			riot: data("riot", self.riot)?,
+			email: data("email", self.email)?,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: data("twitter", self.twitter)?,
+		})
+	}
+}
+
+fn data(field: &'static str, value: Vec<u8>) -> Result<Data, &'static str> {
+	if value.is_empty() {
+		return Ok(Data::None)
+	}
+	value.try_into().map(Data::Raw).map_err(|_| field)
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_identity::Config {}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The accounts and their identities.
+		pub identities: Vec<(T::AccountId, GenesisIdentity)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (who, identity) in &self.identities {
+				let info = identity.clone().into_info().unwrap_or_else(|field| {
+					panic!("Genesis identity of {:?} has a `{}` over 32 bytes", who, field)
+				});
+				pallet_identity::Pallet::<T>::set_identity(
+					frame_system::RawOrigin::Signed(who.clone()).into(),
+					Box::new(info),
+				)
+				.unwrap_or_else(|e| {
+					panic!("Failed to set the genesis identity of {:?}: {:?}", who, e.error)
+				});
+			}
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use crate::{mock::*, GenesisIdentity};
+use frame_support::traits::ReservableCurrency;
+use pallet_identity::Data;
+
+fn identity(display: &[u8]) -> GenesisIdentity {
+	GenesisIdentity { display: display.to_vec(), ..Default::default() }
+}
+
+#[test]
+fn genesis_sets_identities_and_reserves_deposits() {
+	let alice = GenesisIdentity { web: b"https://alice.example".to_vec(), ..identity(b"Alice") };
+	new_test_ext(vec![(1, alice)]).execute_with(|| {
+		let registration = Identity::identity(1).unwrap();
+		assert_eq!(registration.info.display, Data::Raw(b"Alice".to_vec().try_into().unwrap()));
+		assert_eq!(
+			registration.info.web,
+			Data::Raw(b"https://alice.example".to_vec().try_into().unwrap())
+		);
+		assert_eq!(registration.info.legal, Data::None);
+		assert!(registration.judgements.is_empty());
+
+		assert_eq!(registration.deposit, 10);
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert!(Identity::identity(2).is_none());
+	});
+}
+
+#[test]
+#[should_panic(expected = "has a `display` over 32 bytes")]
+fn genesis_rejects_long_fields() {
+	new_test_ext(vec![(1, identity(&[b'a'; 33]))]);
+}
+
+#[test]
+#[should_panic(expected = "Failed to set the genesis identity of 2")]
+fn genesis_requires_the_deposit() {
+	new_test_ext(vec![(2, identity(b"Bob"))]);
+}
@@ -0,0 +1,161 @@
+//! RPC interface for the template pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::{error::SubscriptionClosed, RpcResult},
+	proc_macros::rpc,
+	types::{
+		error::{CallError, ErrorObject},
+		SubscriptionResult,
+	},
+	SubscriptionSink,
+};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_template::runtime_api::TemplateApi as TemplateRuntimeApi;
+
+#[rpc(client, server)]
+pub trait TemplateApi<BlockHash, AccountId> {
+	/// Get the value of `Something` at the given block, or at the best block if none is given.
+	#[method(name = "template_getSomething")]
+	fn get_something(&self, at: Option<BlockHash>) -> RpcResult<Option<u32>>;
+
+	/// Get the value stored by `account` at the given block, or at the best block if none is
+	/// given.
+	#[method(name = "template_getAccountSomething")]
+	fn get_account_something(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<u32>>;
+
+	/// Subscribe to changes of `Something` on the best chain.
+	///
+	/// The subscription is closed with an error if `Something` can't be queried.
+	#[subscription(
+		name = "template_subscribeSomething" => "template_something",
+		unsubscribe = "template_unsubscribeSomething",
+		item = Option<u32>
+	)]
+	fn subscribe_something(&self);
+}
+
+/// Provides RPC methods to query the template pallet's state.
+pub struct Template<C, Block> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Template<C, Block> {
+	/// Creates a new instance of the Template Rpc helper.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId> TemplateApiServer<<Block as BlockT>::Hash, AccountId>
+	for Template<C, Block>
+where
+	Block: BlockT,
+	AccountId: Codec + Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C: Send + Sync + 'static,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+{
+	fn get_something(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<Option<u32>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		something_at(&*self.client, at_hash)
+	}
+
+	fn get_account_something(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<u32>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.account_something(at_hash, account)
+			.map_err(|e| runtime_error("Unable to query the account's something.", e))
+	}
+
+	fn subscribe_something(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		let client = self.client.clone();
+		let initial = something_at(&*client, client.info().best_hash);
+		let last = initial.as_ref().ok().cloned();
+
+		// Only forward new best blocks where the value actually changed, and the first failed
+		// query, which ends the subscription.
+		let changes = client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.map(move |notification| something_at(&*client, notification.hash))
+			.scan(last, |last, something| {
+				let item = match something {
+					Ok(something) if last.as_ref() == Some(&something) => None,
+					Ok(something) => {
+						*last = Some(something);
+						Some(Ok(something))
+					},
+					Err(e) => Some(Err(e)),
+				};
+				future::ready(Some(item))
+			})
+			.filter_map(future::ready);
+		let stream = futures::stream::once(future::ready(initial)).chain(changes);
+
+		let fut = async move {
+			// Tell the subscriber why the subscription ended instead of just going quiet.
+			if let SubscriptionClosed::Failed(e) = sink.pipe_from_try_stream(stream).await {
+				sink.close(e);
+			}
+		};
+		self.executor.spawn("template-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+fn something_at<C, Block, AccountId>(
+	client: &C,
+	at: <Block as BlockT>::Hash,
+) -> RpcResult<Option<u32>>
+where
+	Block: BlockT,
+	AccountId: Codec,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+{
+	client
+		.runtime_api()
+		.something(at)
+		.map_err(|e| runtime_error("Unable to query something.", e))
+}
+
+fn runtime_error(message: &str, e: sp_api::ApiError) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), message, Some(e.to_string())))
+		.into()
+}
@@ -5,7 +5,16 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::v2::*;
+use frame_support::traits::fungible::{InspectHold, Mutate, MutateHold};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Zero};
+
+/// An account with enough funds for storage deposits.
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::set_balance(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	caller
+}
 
 #[benchmarks]
 mod benchmarks {
@@ -14,21 +23,47 @@ mod benchmarks {
 	#[benchmark]
 	fn do_something() {
 		let value = 100u32.into();
-		let caller: T::AccountId = whitelisted_caller();
+		let caller = funded_caller::<T>();
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		do_something(RawOrigin::Signed(caller.clone()), value);
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert_eq!(Template::<T>::something(), Some(value));
+		assert_eq!(AccountSomething::<T>::get(&caller), Some(value));
+		let reason = HoldReason::StorageDeposit.into();
+		assert_eq!(T::Currency::balance_on_hold(&reason, &caller), Template::<T>::deposit(&caller));
 	}
 
 	#[benchmark]
 	fn cause_error() {
-		Something::<T>::put(100u32);
+		Something::<T>::put(SomethingInfo { value: 100u32, updated_at: Zero::zero() });
 		let caller: T::AccountId = whitelisted_caller();
 		#[extrinsic_call]
 		cause_error(RawOrigin::Signed(caller));
 
-		assert_eq!(Something::<T>::get(), Some(101u32));
+		assert_eq!(Template::<T>::something(), Some(101u32));
+	}
+
+	#[benchmark]
+	fn clear_something() -> Result<(), BenchmarkError> {
+		let caller = funded_caller::<T>();
+		let reason = HoldReason::StorageDeposit.into();
+		T::Currency::hold(&reason, &caller, Template::<T>::deposit(&caller))?;
+		AccountSomething::<T>::insert(&caller, 100u32);
+		#[extrinsic_call]
+		clear_something(RawOrigin::Signed(caller.clone()));
+
+		assert_eq!(AccountSomething::<T>::get(&caller), None);
+		assert!(T::Currency::balance_on_hold(&reason, &caller).is_zero());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_something_unsigned() {
+		let block_number = frame_system::Pallet::<T>::block_number();
+		#[extrinsic_call]
+		submit_something_unsigned(RawOrigin::None, block_number, 100u32);
+
+		assert_eq!(Template::<T>::something(), Some(100u32));
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
@@ -13,34 +13,172 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod runtime_api;
 pub mod weights;
 pub use weights::*;
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::fungible;
+use scale_info::TypeInfo;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::RuntimeDebug;
+
+/// The balance type of storage deposits.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The global value stored in `Something`, along with when it was last written.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SomethingInfo<BlockNumber> {
+	/// The stored value.
+	pub value: u32,
+	/// The block at which the value was last written.
+	pub updated_at: BlockNumber,
+}
+
+/// The key type of the keys the offchain worker signs its transactions with.
+///
+/// Keys can be added to the node's keystore through the `author_insertKey` RPC. When none is
+/// available the offchain worker falls back to an unsigned transaction.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// The offchain worker's signing crypto, an sr25519 key under [`KEY_TYPE`].
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs the offchain worker's transactions with a [`KEY_TYPE`] key from the keystore.
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Offchain local storage key of the URL the offchain worker fetches its value from, overriding
+/// [`DEFAULT_ENDPOINT`].
+pub const ENDPOINT_STORAGE_KEY: &[u8] = b"template::endpoint";
+
+/// Offchain local storage key of the block at which the offchain worker last sent a signed
+/// transaction.
+const LAST_SIGNED_SUBMISSION_STORAGE_KEY: &[u8] = b"template::last-signed-submission";
+
+/// URL the offchain worker fetches its value from. The response body must be a decimal `u32`.
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:8000/something";
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{
+			fungible::{InspectHold, MutateHold},
+			tokens::Precision,
+		},
+	};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer, SubmitTransaction,
+		},
+		pallet_prelude::*,
+	};
+	use sp_runtime::{
+		offchain::{
+			http,
+			storage::{StorageRetrievalError, StorageValueRef},
+			Duration, StorageKind,
+		},
+		traits::Zero,
+		Saturating,
+	};
+	use sp_std::vec::Vec;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// Type representing the weight of this pallet
 		type WeightInfo: WeightInfo;
+		/// The identifier type for the offchain worker's signing keys.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Priority of the offchain worker's unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+		/// Number of blocks to wait between two transactions of the offchain worker, signed or
+		/// unsigned.
+		#[pallet::constant]
+		type UnsignedInterval: Get<BlockNumberFor<Self>>;
+		/// The currency storage deposits are held in.
+		type Currency: fungible::Mutate<Self::AccountId>
+			+ fungible::MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+		/// The deposit held for each value an account stores.
+		#[pallet::constant]
+		type DepositPerItem: Get<BalanceOf<Self>>;
+		/// The deposit held for each byte of a stored value, including its storage key.
+		#[pallet::constant]
+		type DepositPerByte: Get<BalanceOf<Self>>;
+	}
+
+	/// A reason for the pallet to hold funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The deposit for a value stored by the account through `do_something`.
+		StorageDeposit,
 	}
 
 	// The pallet's runtime storage items.
 	// https://docs.substrate.io/main-docs/build/runtime-storage/
 	#[pallet::storage]
-	#[pallet::getter(fn something)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/main-docs/build/runtime-storage/#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	pub type Something<T: Config> = StorageValue<_, SomethingInfo<BlockNumberFor<T>>>;
+
+	/// The value stored by each account through `do_something`.
+	#[pallet::storage]
+	#[pallet::getter(fn account_something)]
+	pub type AccountSomething<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+	/// The first block at which the offchain worker may submit an unsigned transaction again.
+	#[pallet::storage]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Initial value of `Something`.
+		pub something: Option<u32>,
+		/// Initial per-account values.
+		pub account_something: Vec<(T::AccountId, u32)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(something) = self.something {
+				Pallet::<T>::put_something(something);
+			}
+			for (who, something) in &self.account_something {
+				<AccountSomething<T>>::insert(who, something);
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -50,6 +188,10 @@ pub mod pallet {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [something, who]
 		SomethingStored { something: u32, who: T::AccountId },
+		/// An account removed its stored value. [who]
+		SomethingCleared { who: T::AccountId },
+		/// The offchain worker submitted a value without a signature. [something]
+		SomethingSubmitted { something: u32 },
 	}
 
 	// Errors inform users that something went wrong.
@@ -61,13 +203,72 @@ pub mod pallet {
 		StorageOverflow,
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Fetch a value over HTTP and submit it on chain, signed with a [`KEY_TYPE`] key if the
+		/// node has one and unsigned otherwise.
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let something = match Self::fetch_something() {
+				Ok(something) => something,
+				Err(e) => {
+					log::debug!(target: "runtime::template", "Failed to fetch something: {:?}", e);
+					return
+				},
+			};
+
+			if let Err(e) = Self::submit_something(block_number, something) {
+				log::error!(target: "runtime::template", "Failed to submit something: {}", e);
+			}
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Only accept submissions of the local offchain worker, one per `UnsignedInterval` and
+		/// none for future blocks.
+		///
+		/// The submissions are neither signed nor paid for, so they are rejected when they come
+		/// from the network and aren't propagated: only the node that made one includes it when
+		/// it authors a block.
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::submit_something_unsigned { block_number, something: _ } = call else {
+				return InvalidTransaction::Call.into()
+			};
+			if !matches!(source, TransactionSource::Local | TransactionSource::InBlock) {
+				return InvalidTransaction::Call.into()
+			}
+
+			let next_unsigned_at = <NextUnsignedAt<T>>::get();
+			if &next_unsigned_at > block_number {
+				return InvalidTransaction::Stale.into()
+			}
+			if &<frame_system::Pallet<T>>::block_number() < block_number {
+				return InvalidTransaction::Future.into()
+			}
+
+			ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				.and_provides(next_unsigned_at)
+				.longevity(5)
+				.propagate(false)
+				.build()
+		}
+	}
+
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
-		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
+		/// the caller's storage slot and emits an event. This function must be dispatched by a
+		/// signed extrinsic.
+		///
+		/// A storage deposit is held from the caller when it has no value stored yet, and
+		/// released by `clear_something`. The global `Something` is updated as well for
+		/// compatibility.
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::do_something())]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
@@ -76,8 +277,14 @@ pub mod pallet {
 			// https://docs.substrate.io/main-docs/build/origins/
 			let who = ensure_signed(origin)?;
 
+			// Hold a deposit for a new storage item.
+			if !<AccountSomething<T>>::contains_key(&who) {
+				T::Currency::hold(&HoldReason::StorageDeposit.into(), &who, Self::deposit(&who))?;
+			}
+
 			// Update storage.
-			<Something<T>>::put(something);
+			<AccountSomething<T>>::insert(&who, something);
+			Self::put_something(something);
 
 			// Emit an event.
 			Self::deposit_event(Event::SomethingStored { something, who });
@@ -92,17 +299,165 @@ pub mod pallet {
 			let _who = ensure_signed(origin)?;
 
 			// Read a value from storage.
-			match <Something<T>>::get() {
+			match Self::something() {
 				// Return an error if the value has not been set.
 				None => return Err(Error::<T>::NoneValue.into()),
 				Some(old) => {
 					// Increment the value read from storage; will error in the event of overflow.
 					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
+					Self::put_something(new);
 					Ok(())
 				},
 			}
 		}
+
+		/// Remove the value stored by the caller and release its storage deposit.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// Return an error if the caller has nothing stored.
+			ensure!(<AccountSomething<T>>::contains_key(&who), Error::<T>::NoneValue);
+			<AccountSomething<T>>::remove(&who);
+
+			// Values set at genesis or before deposits were introduced have no deposit held.
+			let reason = HoldReason::StorageDeposit.into();
+			let deposit = T::Currency::balance_on_hold(&reason, &who);
+			if !deposit.is_zero() {
+				T::Currency::release(&reason, &who, deposit, Precision::Exact)?;
+			}
+
+			Self::deposit_event(Event::SomethingCleared { who });
+			Ok(())
+		}
+
+		/// Store a value fetched by the offchain worker, submitted as an unsigned transaction.
+		///
+		/// Only the global `Something` is updated since there is no caller to attribute it to.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_something_unsigned())]
+		pub fn submit_something_unsigned(
+			origin: OriginFor<T>,
+			block_number: BlockNumberFor<T>,
+			something: u32,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			Self::put_something(something);
+			<NextUnsignedAt<T>>::put(block_number + T::UnsignedInterval::get());
+
+			Self::deposit_event(Event::SomethingSubmitted { something });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The global value, without its metadata.
+		pub fn something() -> Option<u32> {
+			<Something<T>>::get().map(|info| info.value)
+		}
+
+		/// The deposit held for the value stored by `who`, proportional to the bytes it takes.
+		pub fn deposit(who: &T::AccountId) -> BalanceOf<T> {
+			let bytes = <AccountSomething<T>>::hashed_key_for(who).len() + u32::max_encoded_len();
+			T::DepositPerByte::get()
+				.saturating_mul((bytes as u32).into())
+				.saturating_add(T::DepositPerItem::get())
+		}
+
+		/// Write the global value, recording the current block number.
+		fn put_something(value: u32) {
+			let updated_at = <frame_system::Pallet<T>>::block_number();
+			<Something<T>>::put(SomethingInfo { value, updated_at });
+		}
+
+		/// Fetch the value to submit from the HTTP endpoint.
+		fn fetch_something() -> Result<u32, http::Error> {
+			let endpoint =
+				sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, ENDPOINT_STORAGE_KEY);
+			let endpoint = match &endpoint {
+				Some(endpoint) =>
+					sp_std::str::from_utf8(endpoint).map_err(|_| http::Error::Unknown)?,
+				None => DEFAULT_ENDPOINT,
+			};
+
+			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
+			let pending = http::Request::get(endpoint)
+				.deadline(deadline)
+				.send()
+				.map_err(|_| http::Error::IoError)?;
+			let response =
+				pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+			if response.code != 200 {
+				log::warn!(target: "runtime::template", "Unexpected status code: {}", response.code);
+				return Err(http::Error::Unknown)
+			}
+
+			let body = response.body().collect::<Vec<u8>>();
+			sp_std::str::from_utf8(&body)
+				.ok()
+				.and_then(|body| body.trim().parse().ok())
+				.ok_or(http::Error::Unknown)
+		}
+
+		/// Submit `something` through `do_something` if the node has a signing key, or through
+		/// `submit_something_unsigned` otherwise.
+		fn submit_something(
+			block_number: BlockNumberFor<T>,
+			something: u32,
+		) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if signer.can_sign() {
+				if !Self::can_send_signed(block_number) {
+					return Err("Too early to send a signed transaction")
+				}
+				return match signer.send_signed_transaction(|_| Call::do_something { something }) {
+					Some((_, Ok(()))) => {
+						Self::record_signed_submission(block_number);
+						Ok(())
+					},
+					Some((_, Err(()))) => Err("Failed to submit signed transaction"),
+					None => Err("No local account available"),
+				}
+			}
+
+			if <NextUnsignedAt<T>>::get() > block_number {
+				return Err("Too early to send an unsigned transaction")
+			}
+
+			let call = Call::submit_something_unsigned { block_number, something };
+			SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+				.map_err(|()| "Unable to submit unsigned transaction")
+		}
+
+		/// Whether the offchain worker may send a signed transaction at `block_number`, i.e. it
+		/// recorded none in the `UnsignedInterval` blocks before.
+		///
+		/// Signed transactions aren't rate limited on chain, so without this the offchain worker
+		/// would spend a transaction fee on every block.
+		fn can_send_signed(block_number: BlockNumberFor<T>) -> bool {
+			let last_submission = StorageValueRef::persistent(LAST_SIGNED_SUBMISSION_STORAGE_KEY);
+			!matches!(
+				last_submission.get::<BlockNumberFor<T>>(),
+				Ok(Some(last)) if block_number < last + T::UnsignedInterval::get()
+			)
+		}
+
+		/// Record in offchain local storage that a signed transaction was sent at `block_number`.
+		///
+		/// Only done once the transaction is in the pool, so a failed submission is retried at
+		/// the next block.
+		fn record_signed_submission(block_number: BlockNumberFor<T>) {
+			let last_submission = StorageValueRef::persistent(LAST_SIGNED_SUBMISSION_STORAGE_KEY);
+			let _ = last_submission.mutate(
+				|last: Result<Option<BlockNumberFor<T>>, StorageRetrievalError>| match last {
+					// The worker of a later block already recorded its submission.
+					Ok(Some(last)) if last > block_number => Err(()),
+					_ => Ok(block_number),
+				},
+			);
+		}
 	}
 }
@@ -0,0 +1,89 @@
+//! Storage migrations for the template pallet.
+
+use super::*;
+use frame_support::{
+	pallet_prelude::*,
+	storage_alias,
+	traits::{GetStorageVersion, OnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// The storage layout before any versioning.
+mod v0 {
+	use super::*;
+
+	/// `Something` was a bare `u32`.
+	#[storage_alias]
+	pub type Something<T: Config> = StorageValue<Pallet<T>, u32>;
+}
+
+/// Migrates `Something` from a bare `u32` to [`SomethingInfo`].
+pub mod v1 {
+	use super::*;
+
+	/// Wraps the existing value in a [`SomethingInfo`] stamped with the upgrade's block number.
+	///
+	/// A stored value that doesn't decode as a `u32` is removed, as it could not be decoded as a
+	/// [`SomethingInfo`] either and would otherwise stay behind as unreadable storage.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 0 {
+				log::info!(
+					target: "runtime::template",
+					"Skipping migration to v1, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let updated_at = <frame_system::Pallet<T>>::block_number();
+			let translated = Something::<T>::translate::<u32, _>(|old| {
+				old.map(|value| SomethingInfo::<BlockNumberFor<T>> { value, updated_at })
+			});
+			if translated.is_err() {
+				log::error!(
+					target: "runtime::template",
+					"Failed to decode the old `Something`, removing it"
+				);
+				Something::<T>::kill();
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			log::info!(target: "runtime::template", "Migrated storage to v1");
+
+			T::DbWeight::get().reads_writes(3, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok(v0::Something::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let old = Option::<u32>::decode(&mut &state[..])
+				.map_err(|_| "Failed to decode the pre-upgrade `Something`")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"Storage version must be 1 after the migration"
+			);
+			ensure!(
+				Pallet::<T>::something() == old,
+				"`Something` must keep its value through the migration"
+			);
+			ensure!(
+				Something::<T>::exists() == old.is_some(),
+				"An undecodable `Something` must be removed by the migration"
+			);
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,15 @@
+//! Runtime API definition for the template pallet.
+//!
+//! Lets RPCs and front-ends read the pallet's state without computing storage keys themselves.
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait TemplateApi<AccountId> where AccountId: Codec {
+		/// The global value last written through `do_something`.
+		fn something() -> Option<u32>;
+
+		/// The value stored by `who` through `do_something`.
+		fn account_something(who: AccountId) -> Option<u32>;
+	}
+}
@@ -1,5 +1,27 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::v1::MigrateToV1, mock::*, AccountSomething, Error, Event, HoldReason,
+	NextUnsignedAt, Something, SomethingInfo, DEFAULT_ENDPOINT,
+};
+use codec::Decode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungible::{Inspect, InspectHold},
+		GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion,
+	},
+};
+use parking_lot::RwLock;
+use sp_core::offchain::{
+	testing::{self, OffchainState, PoolState},
+	OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	BuildStorage, TokenError,
+};
+use std::sync::Arc;
 
 #[test]
 fn it_works_for_default_value() {
@@ -10,6 +32,7 @@ fn it_works_for_default_value() {
 		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
 		// Read pallet storage and assert an expected result.
 		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::account_something(1), Some(42));
 		// Assert that the correct event was deposited
 		System::assert_last_event(Event::SomethingStored { something: 42, who: 1 }.into());
 	});
@@ -25,3 +48,322 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn accounts_do_not_clobber_each_other() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 7));
+		// Each account keeps its own value while the global one follows the latest write.
+		assert_eq!(TemplateModule::account_something(1), Some(42));
+		assert_eq!(TemplateModule::account_something(2), Some(7));
+		assert_eq!(TemplateModule::something(), Some(7));
+	});
+}
+
+#[test]
+fn clear_something_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 7));
+
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::account_something(1), None);
+		assert_eq!(TemplateModule::account_something(2), Some(7));
+		System::assert_last_event(Event::SomethingCleared { who: 1 }.into());
+	});
+}
+
+#[test]
+fn correct_error_for_clearing_none_value() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::clear_something(RuntimeOrigin::signed(1)),
+			Error::<Test>::NoneValue
+		);
+	});
+}
+
+fn held(who: u64) -> u128 {
+	Balances::balance_on_hold(&HoldReason::StorageDeposit.into(), &who)
+}
+
+#[test]
+fn do_something_holds_a_deposit_once() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::deposit(&1), DEPOSIT);
+
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_eq!(held(1), DEPOSIT);
+		assert_eq!(Balances::balance(&1), 100 - DEPOSIT);
+
+		// Overwriting the value doesn't take another deposit.
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 7));
+		assert_eq!(held(1), DEPOSIT);
+	});
+}
+
+#[test]
+fn clear_something_releases_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+
+		assert_eq!(held(1), 0);
+		assert_eq!(Balances::balance(&1), 100);
+	});
+}
+
+#[test]
+fn clear_something_works_without_deposit() {
+	new_test_ext().execute_with(|| {
+		// E.g. a value set at genesis.
+		AccountSomething::<Test>::insert(3, 42);
+
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(3)));
+		assert_eq!(TemplateModule::account_something(3), None);
+	});
+}
+
+#[test]
+fn do_something_requires_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::do_something(RuntimeOrigin::signed(3), 42),
+			TokenError::FundsUnavailable
+		);
+		assert_eq!(TemplateModule::account_something(3), None);
+	});
+}
+
+#[test]
+fn genesis_config_works() {
+	let storage = RuntimeGenesisConfig {
+		system: Default::default(),
+		balances: Default::default(),
+		template_module: crate::GenesisConfig {
+			something: Some(100),
+			account_something: vec![(1, 10), (2, 20)],
+		},
+	}
+	.build_storage()
+	.unwrap();
+
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(TemplateModule::something(), Some(100));
+		assert_eq!(TemplateModule::account_something(1), Some(10));
+		assert_eq!(TemplateModule::account_something(2), Some(20));
+		// The initial value lets `cause_error` succeed on a fresh chain.
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::something(), Some(101));
+	});
+}
+
+/// Test externalities with offchain worker and transaction pool extensions.
+fn new_offchain_test_ext(
+) -> (sp_io::TestExternalities, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>) {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	t.register_extension(OffchainDbExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	(t, offchain_state, pool_state)
+}
+
+fn expect_something_request(state: &mut OffchainState, body: &[u8]) {
+	state.expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: DEFAULT_ENDPOINT.into(),
+		response: Some(body.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_without_keys() {
+	let (mut t, offchain_state, pool_state) = new_offchain_test_ext();
+	expect_something_request(&mut offchain_state.write(), b"42");
+
+	t.execute_with(|| {
+		UintAuthorityId::set_all_keys(Vec::<u64>::new());
+		TemplateModule::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		assert_eq!(
+			tx.call,
+			RuntimeCall::TemplateModule(crate::Call::submit_something_unsigned {
+				block_number: 1,
+				something: 42
+			})
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_signed_with_keys() {
+	let (mut t, offchain_state, pool_state) = new_offchain_test_ext();
+	expect_something_request(&mut offchain_state.write(), b"42\n");
+
+	t.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![7u64]);
+		TemplateModule::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(
+			tx.call,
+			RuntimeCall::TemplateModule(crate::Call::do_something { something: 42 })
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_waits_between_signed_submissions() {
+	let (mut t, offchain_state, pool_state) = new_offchain_test_ext();
+	for body in [b"42", b"43", b"44"] {
+		expect_something_request(&mut offchain_state.write(), body);
+	}
+
+	t.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![7u64]);
+		TemplateModule::offchain_worker(1);
+		assert_eq!(pool_state.write().transactions.drain(..).count(), 1);
+
+		// Within `UnsignedInterval` of the last submission.
+		TemplateModule::offchain_worker(5);
+		assert!(pool_state.read().transactions.is_empty());
+
+		TemplateModule::offchain_worker(6);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.call,
+			RuntimeCall::TemplateModule(crate::Call::do_something { something: 44 })
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_ignores_invalid_response() {
+	let (mut t, offchain_state, pool_state) = new_offchain_test_ext();
+	expect_something_request(&mut offchain_state.write(), b"not a number");
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn submit_something_unsigned_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::submit_something_unsigned(RuntimeOrigin::none(), 1, 42));
+		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(NextUnsignedAt::<Test>::get(), 6);
+		System::assert_last_event(Event::SomethingSubmitted { something: 42 }.into());
+
+		// Signed origins are rejected.
+		assert_noop!(
+			TemplateModule::submit_something_unsigned(RuntimeOrigin::signed(1), 1, 42),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_stale_and_future_submissions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let call = |block_number| crate::Call::submit_something_unsigned::<Test> {
+			block_number,
+			something: 42,
+		};
+
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::Local, &call(3)));
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::Local, &call(4)),
+			InvalidTransaction::Future.into()
+		);
+
+		NextUnsignedAt::<Test>::put(5);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::Local, &call(3)),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_submissions_from_the_network() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let call =
+			crate::Call::submit_something_unsigned::<Test> { block_number: 3, something: 42 };
+
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::Local, &call));
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::InBlock, &call));
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+	});
+}
+
+#[test]
+fn something_records_when_it_was_updated() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 3 }));
+	});
+}
+
+#[test]
+fn migration_to_v1_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(10);
+		// Write the value as it was stored before versioning.
+		StorageVersion::new(0).put::<TemplateModule>();
+		frame_support::storage::unhashed::put(&Something::<Test>::hashed_key(), &42u32);
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 10 }));
+		assert_eq!(TemplateModule::something(), Some(42));
+	});
+}
+
+#[test]
+fn migration_to_v1_removes_undecodable_value() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		frame_support::storage::unhashed::put_raw(&Something::<Test>::hashed_key(), &[1, 2]);
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+		assert!(!Something::<Test>::exists());
+	});
+}
+
+#[test]
+fn migration_to_v1_is_skipped_when_already_applied() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<TemplateModule>();
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		let before = Something::<Test>::get();
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Something::<Test>::get(), before);
+	});
+}
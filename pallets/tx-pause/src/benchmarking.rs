@@ -0,0 +1,46 @@
+//! Benchmarking setup for pallet-tx-pause
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as TxPause;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{EnsureOrigin, Get};
+use sp_std::vec;
+
+/// A name of `MaxNameLen` bytes, the worst case for hashing the storage key.
+fn name<T: Config>(byte: u8) -> NameOf<T> {
+	NameOf::<T>::truncate_from(vec![byte; T::MaxNameLen::get() as usize])
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn pause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (pallet_name, call_name) = (name::<T>(b'p'), Some(name::<T>(b'c')));
+		#[extrinsic_call]
+		pause(origin as T::RuntimeOrigin, pallet_name.clone(), call_name.clone());
+
+		assert!(PausedCalls::<T>::contains_key((pallet_name, call_name)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn unpause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::UnpauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (pallet_name, call_name) = (name::<T>(b'p'), Some(name::<T>(b'c')));
+		PausedCalls::<T>::insert((pallet_name.clone(), call_name.clone()), ());
+		#[extrinsic_call]
+		unpause(origin as T::RuntimeOrigin, pallet_name.clone(), call_name.clone());
+
+		assert!(!PausedCalls::<T>::contains_key((pallet_name, call_name)));
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(TxPause, crate::mock::new_test_ext(), crate::mock::Test);
+}
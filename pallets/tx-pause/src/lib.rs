@@ -0,0 +1,264 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Transaction Pause Pallet
+//!
+//! An emergency switch to disable all calls of a pallet, or single calls, without a runtime
+//! upgrade. Calls are named as in the metadata, e.g. `TemplateModule` or
+//! `Balances::transfer_keep_alive`.
+//!
+//! The pallet is the runtime's `BaseCallFilter`, so paused calls fail to dispatch, including
+//! when nested in other calls. The [`CheckTxPause`] signed extension additionally rejects
+//! transactions with a paused call at transaction-pool validation time.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{CallMetadata, Contains, GetCallMetadata},
+	BoundedVec,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::{fmt, marker::PhantomData};
+
+/// The name of a pallet or call.
+pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Origin allowed to pause calls.
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Origin allowed to unpause calls.
+		type UnpauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Pallets whose calls can't be paused, e.g. `System`, `Timestamp`, this pallet and the
+		/// governance pallets needed to unpause calls again.
+		type UnpausablePallets: Contains<NameOf<Self>>;
+		/// Maximum length of pallet and call names.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+		/// Type representing the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The paused pallets, with no call name, and calls.
+	#[pallet::storage]
+	pub type PausedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, (NameOf<T>, Option<NameOf<T>>), (), OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pallet, or one of its calls, was paused. [pallet_name, call_name]
+		CallPaused { pallet_name: NameOf<T>, call_name: Option<NameOf<T>> },
+		/// A pallet, or one of its calls, was unpaused. [pallet_name, call_name]
+		CallUnpaused { pallet_name: NameOf<T>, call_name: Option<NameOf<T>> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The pallet or call is already paused.
+		IsPaused,
+		/// The pallet or call is not paused.
+		IsUnpaused,
+		/// The pallet is one of `UnpausablePallets`.
+		Unpausable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause all calls of `pallet_name`, or only `call_name` if given.
+		///
+		/// The origin for this call must be `PauseOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			pallet_name: NameOf<T>,
+			call_name: Option<NameOf<T>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			ensure!(!T::UnpausablePallets::contains(&pallet_name), Error::<T>::Unpausable);
+			let key = (pallet_name, call_name);
+			ensure!(!<PausedCalls<T>>::contains_key(&key), Error::<T>::IsPaused);
+
+			<PausedCalls<T>>::insert(&key, ());
+			let (pallet_name, call_name) = key;
+			Self::deposit_event(Event::CallPaused { pallet_name, call_name });
+			Ok(())
+		}
+
+		/// Unpause what `pause` paused with the same arguments.
+		///
+		/// The origin for this call must be `UnpauseOrigin`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			pallet_name: NameOf<T>,
+			call_name: Option<NameOf<T>>,
+		) -> DispatchResult {
+			T::UnpauseOrigin::ensure_origin(origin)?;
+
+			let key = (pallet_name, call_name);
+			ensure!(<PausedCalls<T>>::contains_key(&key), Error::<T>::IsUnpaused);
+
+			<PausedCalls<T>>::remove(&key);
+			let (pallet_name, call_name) = key;
+			Self::deposit_event(Event::CallUnpaused { pallet_name, call_name });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether the call `call_name` of `pallet_name` is paused, on its own or with its pallet.
+		pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+			let Ok(pallet_name) = NameOf::<T>::try_from(pallet_name.as_bytes().to_vec()) else {
+				return false
+			};
+			if <PausedCalls<T>>::contains_key((&pallet_name, None::<NameOf<T>>)) {
+				return true
+			}
+
+			match NameOf::<T>::try_from(call_name.as_bytes().to_vec()) {
+				Ok(call_name) => <PausedCalls<T>>::contains_key((pallet_name, Some(call_name))),
+				Err(_) => false,
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T>
+where
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+{
+	/// Whether `call` is paused.
+	pub fn is_call_paused(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+		Self::is_paused(pallet_name, function_name)
+	}
+}
+
+/// Filters out paused calls, to be used as the runtime's `BaseCallFilter`.
+impl<T: Config> Contains<<T as frame_system::Config>::RuntimeCall> for Pallet<T>
+where
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+{
+	fn contains(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		!Self::is_call_paused(call)
+	}
+}
+
+/// Rejects signed and unsigned transactions whose call is paused, so they don't linger in the
+/// transaction pool until their dispatch fails.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckTxPause<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckTxPause<T> {
+	/// Create new `SignedExtension` to check for paused calls.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckTxPause<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for CheckTxPause<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckTxPause")
+	}
+}
+
+impl<T: Config + Send + Sync> CheckTxPause<T>
+where
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+{
+	fn check(call: &<T as frame_system::Config>::RuntimeCall) -> TransactionValidity {
+		if Pallet::<T>::is_call_paused(call) {
+			return InvalidTransaction::Call.into()
+		}
+		Ok(ValidTransaction::default())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckTxPause<T>
+where
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+{
+	const IDENTIFIER: &'static str = "CheckTxPause";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		Self::check(call)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+
+	fn validate_unsigned(
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		Self::check(call)
+	}
+
+	fn pre_dispatch_unsigned(
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Self::validate_unsigned(call, info, len).map(|_| ())
+	}
+}
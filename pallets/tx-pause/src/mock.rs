@@ -0,0 +1,93 @@
+use crate as pallet_tx_pause;
+use frame_support::traits::{ConstU128, ConstU16, ConstU32, ConstU64, Contains};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		TxPause: pallet_tx_pause,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = TxPause;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u128;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU128<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+/// `System` and `TxPause` itself can't be paused.
+pub struct UnpausablePallets;
+
+impl Contains<pallet_tx_pause::NameOf<Test>> for UnpausablePallets {
+	fn contains(pallet_name: &pallet_tx_pause::NameOf<Test>) -> bool {
+		matches!(pallet_name.as_slice(), b"System" | b"TxPause")
+	}
+}
+
+impl pallet_tx_pause::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type PauseOrigin = frame_system::EnsureRoot<u64>;
+	type UnpauseOrigin = frame_system::EnsureRoot<u64>;
+	type UnpausablePallets = UnpausablePallets;
+	type MaxNameLen = ConstU32<64>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage with account 1 endowed.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		system: Default::default(),
+		balances: pallet_balances::GenesisConfig { balances: vec![(1, 100)] },
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+	// Go past genesis block so events get deposited
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
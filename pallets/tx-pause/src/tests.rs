@@ -0,0 +1,108 @@
+use crate::{mock::*, CheckTxPause, Error, Event, NameOf};
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo};
+use sp_runtime::{
+	traits::{Dispatchable, SignedExtension},
+	transaction_validity::InvalidTransaction,
+	DispatchError,
+};
+
+fn name(name: &str) -> NameOf<Test> {
+	name.as_bytes().to_vec().try_into().unwrap()
+}
+
+fn transfer_keep_alive() -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive { dest: 2, value: 10 })
+}
+
+fn transfer_all() -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_all { dest: 2, keep_alive: false })
+}
+
+#[test]
+fn pausing_a_call_filters_it() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(
+			RuntimeOrigin::root(),
+			name("Balances"),
+			Some(name("transfer_keep_alive"))
+		));
+		System::assert_last_event(
+			Event::CallPaused {
+				pallet_name: name("Balances"),
+				call_name: Some(name("transfer_keep_alive")),
+			}
+			.into(),
+		);
+
+		assert_noop!(
+			transfer_keep_alive().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		// Other calls of the pallet are still allowed.
+		assert_ok!(transfer_all().dispatch(RuntimeOrigin::signed(1)));
+	});
+}
+
+#[test]
+fn pausing_a_pallet_filters_all_its_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name("Balances"), None));
+
+		assert!(TxPause::is_call_paused(&transfer_keep_alive()));
+		assert!(TxPause::is_call_paused(&transfer_all()));
+
+		assert_ok!(TxPause::unpause(RuntimeOrigin::root(), name("Balances"), None));
+		System::assert_last_event(
+			Event::CallUnpaused { pallet_name: name("Balances"), call_name: None }.into(),
+		);
+		assert_ok!(transfer_keep_alive().dispatch(RuntimeOrigin::signed(1)));
+	});
+}
+
+#[test]
+fn paused_calls_are_invalid_transactions() {
+	new_test_ext().execute_with(|| {
+		let call = transfer_keep_alive();
+		let info = call.get_dispatch_info();
+		assert_ok!(CheckTxPause::<Test>::new().validate(&1, &call, &info, 0));
+
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name("Balances"), None));
+
+		assert_eq!(
+			CheckTxPause::<Test>::new().validate(&1, &call, &info, 0),
+			Err(InvalidTransaction::Call.into())
+		);
+		assert_eq!(
+			CheckTxPause::<Test>::validate_unsigned(&call, &info, 0),
+			Err(InvalidTransaction::Call.into())
+		);
+	});
+}
+
+#[test]
+fn pause_and_unpause_checks() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::pause(RuntimeOrigin::signed(1), name("Balances"), None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TxPause::pause(RuntimeOrigin::root(), name("System"), None),
+			Error::<Test>::Unpausable
+		);
+		assert_noop!(
+			TxPause::unpause(RuntimeOrigin::root(), name("Balances"), None),
+			Error::<Test>::IsUnpaused
+		);
+
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name("Balances"), None));
+		assert_noop!(
+			TxPause::pause(RuntimeOrigin::root(), name("Balances"), None),
+			Error::<Test>::IsPaused
+		);
+		assert_noop!(
+			TxPause::unpause(RuntimeOrigin::signed(1), name("Balances"), None),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,57 @@
+//! Placeholder weights for pallet_tx_pause.
+//!
+//! Hand-written rather than benchmarked. `pause` and `unpause` each check and then insert or
+//! remove a single `PausedCalls` entry, which costs little compute, so 25 µs of ref time leaves
+//! ample margin. The proof size is that of the largest `PausedCalls` key: 565 bytes with the
+//! runtime's `MaxNameLen` of 256, plus around 3 KiB of trie nodes, rounded up to 4 000 bytes.
+//! A larger `MaxNameLen` needs a larger proof size. Database reads and writes are counted from
+//! the code.
+//!
+//! TODO: Regenerate with `benchmark pallet --pallet pallet_tx_pause` from the benchmarks in
+//! `benchmarking.rs` once run on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_tx_pause.
+pub trait WeightInfo {
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+}
+
+/// Weights for pallet_tx_pause using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn pause() -> Weight {
+		Weight::from_parts(25_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn unpause() -> Weight {
+		Weight::from_parts(25_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn pause() -> Weight {
+		Weight::from_parts(25_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn unpause() -> Weight {
+		Weight::from_parts(25_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
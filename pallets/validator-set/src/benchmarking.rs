@@ -0,0 +1,49 @@
+//! Benchmarking setup for pallet-validator-set
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ValidatorSet;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::EnsureOrigin, BoundedVec};
+
+/// Fill the validator set up to `count` validators.
+fn set_validators<T: Config>(count: u32) -> Vec<T::AccountId> {
+	let validators: Vec<T::AccountId> = (0..count).map(|i| account("validator", i, 0)).collect();
+	Validators::<T>::put(BoundedVec::truncate_from(validators.clone()));
+	validators
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn add_validator() -> Result<(), BenchmarkError> {
+		set_validators::<T>(T::MaxValidators::get() - 1);
+		let who: T::AccountId = account("new", 0, 0);
+		let origin =
+			T::AddRemoveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		#[extrinsic_call]
+		add_validator(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(ValidatorSet::<T>::validators().contains(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_validator() -> Result<(), BenchmarkError> {
+		let validators = set_validators::<T>(T::MaxValidators::get());
+		// The last validator is the worst case for the lookup.
+		let who = validators.last().cloned().expect("MaxValidators is non-zero; qed");
+		let origin =
+			T::AddRemoveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		#[extrinsic_call]
+		remove_validator(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!ValidatorSet::<T>::validators().contains(&who));
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(ValidatorSet, crate::mock::new_test_ext(), crate::mock::Test);
+}
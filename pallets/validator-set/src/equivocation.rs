@@ -0,0 +1,244 @@
+//! Reporting of Aura equivocations, i.e. an authority authoring two different blocks for the
+//! same slot.
+//!
+//! `pallet_aura` has no equivocation handling of its own, so this mirrors what `pallet_grandpa`
+//! does for finality equivocations: nodes submit an unsigned `report_equivocation_unsigned` with
+//! the two headers and a session-historical key ownership proof, and valid reports are turned
+//! into an [`AuraEquivocationOffence`] for `pallet_offences`.
+
+use crate::{Call, Config, Error, Pallet};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, KeyOwnerProofSystem},
+	weights::{
+		constants::{WEIGHT_REF_TIME_PER_MICROS, WEIGHT_REF_TIME_PER_NANOS},
+		Weight,
+	},
+};
+use frame_system::offchain::{SendTransactionTypes, SubmitTransaction};
+use scale_info::TypeInfo;
+use sp_consensus_aura::{digests::CompatibleDigestItem, Slot};
+use sp_consensus_slots::EquivocationProof;
+use sp_runtime::{
+	traits::Header,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	DispatchError, KeyTypeId, Perbill, RuntimeAppPublic, RuntimeDebug,
+};
+use sp_session::{GetSessionNumber, GetValidatorCount};
+use sp_staking::{
+	offence::{Kind, Offence, OffenceReportSystem, ReportOffence},
+	SessionIndex,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+const LOG_TARGET: &str = "runtime::validator-set";
+
+/// An Aura equivocation proof for the runtime's header and authority types.
+pub type AuraEquivocationProof<T> =
+	EquivocationProof<frame_system::pallet_prelude::HeaderFor<T>, <T as Config>::AuthorityId>;
+
+/// An encoded key ownership proof, opaque to the node.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, RuntimeDebug, TypeInfo)]
+pub struct OpaqueKeyOwnershipProof(Vec<u8>);
+
+impl OpaqueKeyOwnershipProof {
+	/// Wrap an encoded key ownership proof.
+	pub fn new(inner: Vec<u8>) -> OpaqueKeyOwnershipProof {
+		OpaqueKeyOwnershipProof(inner)
+	}
+
+	/// Decode the proof into the runtime's key ownership proof type.
+	pub fn decode<T: Decode>(self) -> Option<T> {
+		Decode::decode(&mut &self.0[..]).ok()
+	}
+}
+
+/// An authority authored two different blocks for the same slot.
+#[derive(RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct AuraEquivocationOffence<Offender> {
+	/// The slot in which this offence occurred.
+	pub slot: Slot,
+	/// The session index in which this offence occurred.
+	pub session_index: SessionIndex,
+	/// The size of the validator set at the time of the offence.
+	pub validator_set_count: u32,
+	/// The authority which produced the equivocation.
+	pub offender: Offender,
+}
+
+impl<Offender: Clone> Offence<Offender> for AuraEquivocationOffence<Offender> {
+	const ID: Kind = *b"aura:equivocatio";
+	type TimeSlot = Slot;
+
+	fn offenders(&self) -> Vec<Offender> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.slot
+	}
+
+	// The formula is min((3k / n)^2, 1) as for BABE equivocations.
+	fn slash_fraction(&self, offenders_count: u32) -> Perbill {
+		Perbill::from_rational(3 * offenders_count, self.validator_set_count).square()
+	}
+}
+
+/// Aura equivocation offence report system.
+///
+/// Publishes reports through an unsigned `report_equivocation_unsigned` transaction, checks them
+/// against the key ownership proof system `P` and reports valid ones to `R`, usually
+/// `pallet_offences`. Reports are valid for `L` blocks.
+pub struct EquivocationReportSystem<T, R, P, L>(PhantomData<(T, R, P, L)>);
+
+impl<T, R, P, L>
+	OffenceReportSystem<Option<T::AccountId>, (AuraEquivocationProof<T>, T::KeyOwnerProof)>
+	for EquivocationReportSystem<T, R, P, L>
+where
+	T: Config + SendTransactionTypes<Call<T>>,
+	R: ReportOffence<
+		T::AccountId,
+		P::IdentificationTuple,
+		AuraEquivocationOffence<P::IdentificationTuple>,
+	>,
+	P: KeyOwnerProofSystem<(KeyTypeId, T::AuthorityId), Proof = T::KeyOwnerProof>,
+	P::IdentificationTuple: Clone,
+	L: Get<u64>,
+{
+	type Longevity = L;
+
+	fn publish_evidence(evidence: (AuraEquivocationProof<T>, T::KeyOwnerProof)) -> Result<(), ()> {
+		let (equivocation_proof, key_owner_proof) = evidence;
+
+		let call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof),
+			key_owner_proof,
+		};
+		let res = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+		match res {
+			Ok(_) => log::info!(target: LOG_TARGET, "Submitted Aura equivocation report"),
+			Err(e) =>
+				log::error!(target: LOG_TARGET, "Error submitting equivocation report: {:?}", e),
+		}
+		res
+	}
+
+	fn check_evidence(
+		evidence: (AuraEquivocationProof<T>, T::KeyOwnerProof),
+	) -> Result<(), TransactionValidityError> {
+		let (equivocation_proof, key_owner_proof) = evidence;
+
+		// Check the membership proof to extract the offender's id
+		let key = (T::AuthorityId::ID, equivocation_proof.offender);
+		let offender = P::check_proof(key, key_owner_proof).ok_or(InvalidTransaction::BadProof)?;
+
+		// Check if the offence has already been reported, and if so then we can discard the report.
+		if R::is_known_offence(&[offender], &equivocation_proof.slot) {
+			Err(InvalidTransaction::Stale.into())
+		} else {
+			Ok(())
+		}
+	}
+
+	fn process_evidence(
+		reporter: Option<T::AccountId>,
+		evidence: (AuraEquivocationProof<T>, T::KeyOwnerProof),
+	) -> Result<(), DispatchError> {
+		let (equivocation_proof, key_owner_proof) = evidence;
+		let slot = equivocation_proof.slot;
+		let validator_set_count = key_owner_proof.validator_count();
+		let session_index = key_owner_proof.session();
+
+		// Validate the key ownership proof extracting the id of the offender.
+		let offender = P::check_proof(
+			(T::AuthorityId::ID, equivocation_proof.offender.clone()),
+			key_owner_proof,
+		)
+		.ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+
+		// The key ownership proof must be for the session both blocks were authored in, as
+		// `pallet_grandpa` checks the proof's session against the equivocation's set id.
+		let in_session = [&equivocation_proof.first_header, &equivocation_proof.second_header]
+			.into_iter()
+			.all(|header| Pallet::<T>::is_in_session(*header.number(), session_index));
+		if !in_session || !check_equivocation_proof(equivocation_proof) {
+			return Err(Error::<T>::InvalidEquivocationProof.into())
+		}
+
+		let offence =
+			AuraEquivocationOffence { slot, session_index, validator_set_count, offender };
+
+		R::report_offence(reporter.into_iter().collect(), offence)
+			.map_err(|_| Error::<T>::DuplicateOffenceReport)?;
+
+		Ok(())
+	}
+}
+
+/// Check that both headers were sealed by the offender for the proof's slot and differ.
+pub fn check_equivocation_proof<H, AuthorityId>(proof: EquivocationProof<H, AuthorityId>) -> bool
+where
+	H: Header,
+	AuthorityId: RuntimeAppPublic,
+{
+	let verify_header = |mut header: H| {
+		let Some(seal) = header.digest_mut().pop() else { return false };
+		let Some(signature) = CompatibleDigestItem::<AuthorityId::Signature>::as_aura_seal(&seal)
+		else {
+			return false
+		};
+		let pre_digest_slot = header.digest().logs().iter().find_map(|log| {
+			CompatibleDigestItem::<AuthorityId::Signature>::as_aura_pre_digest(log)
+		});
+		if pre_digest_slot != Some(proof.slot) {
+			return false
+		}
+
+		proof.offender.verify(&header.hash(), &signature)
+	};
+
+	if proof.first_header.hash() == proof.second_header.hash() {
+		return false
+	}
+
+	verify_header(proof.first_header.clone()) && verify_header(proof.second_header.clone())
+}
+
+/// Weight of `report_equivocation_unsigned`, estimated like `pallet_grandpa`'s as the call
+/// can't be benchmarked without real equivocations.
+pub fn report_equivocation_weight<T: Config>(validator_count: u32) -> Weight {
+	// checking membership proof
+	Weight::from_parts(35u64 * WEIGHT_REF_TIME_PER_MICROS, 0)
+		.saturating_add(
+			Weight::from_parts(175u64 * WEIGHT_REF_TIME_PER_NANOS, 0)
+				.saturating_mul(validator_count.into()),
+		)
+		.saturating_add(T::DbWeight::get().reads(5))
+		// checking the session both blocks were authored in
+		.saturating_add(T::DbWeight::get().reads(2))
+		// checking both seals of the equivocation proof
+		.saturating_add(Weight::from_parts(110u64 * WEIGHT_REF_TIME_PER_MICROS, 0))
+		// report offence
+		.saturating_add(Weight::from_parts(110u64 * WEIGHT_REF_TIME_PER_MICROS, 0))
+		.saturating_add(T::DbWeight::get().writes(3))
+}
+
+impl<T: Config> Pallet<T> {
+	/// Submit an Aura equivocation report as an unsigned extrinsic. Only useful in an offchain
+	/// context, e.g. from the node's equivocation reporter through the runtime API.
+	pub fn submit_unsigned_equivocation_report(
+		equivocation_proof: AuraEquivocationProof<T>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		T::EquivocationReportSystem::publish_evidence((equivocation_proof, key_owner_proof)).ok()
+	}
+}
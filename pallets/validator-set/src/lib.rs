@@ -0,0 +1,373 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Validator Set Pallet
+//!
+//! Keeps the list of PoA validators and hands it to `pallet_session` as its `SessionManager`.
+//! Validators are added and removed by `AddRemoveOrigin`, and validators reported for an offence
+//! (e.g. a GRANDPA or Aura equivocation) are disabled for the rest of the session and removed
+//! from the set. Changes take effect when the session rotates, which is also when GRANDPA
+//! schedules the matching authority set change.
+//!
+//! The whole set is handed to the session pallet at every rotation, which leaves out validators
+//! without session keys. A new validator thus joins two rotations after it registers its keys
+//! with `Session::set_keys`, however long after being added that is.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod equivocation;
+pub mod runtime_api;
+pub mod weights;
+pub use equivocation::*;
+pub use runtime_api::*;
+pub use sp_consensus_slots::EquivocationProof;
+pub use weights::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::{traits::Convert, Perbill, RuntimeAppPublic};
+use sp_session::{GetSessionNumber, GetValidatorCount};
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OffenceReportSystem, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::{boxed::Box, marker::PhantomData, vec::Vec};
+
+/// Something that can disable a validator until the end of the current session.
+pub trait DisableValidator<AccountId> {
+	/// Disable `who`, returning whether it was an active, not yet disabled validator.
+	fn disable(who: &AccountId) -> bool;
+}
+
+impl<AccountId> DisableValidator<AccountId> for () {
+	fn disable(_who: &AccountId) -> bool {
+		false
+	}
+}
+
+/// `pallet_session` disables validators for the rest of the session, e.g. excluding them from
+/// Aura slot assignment through `DisabledValidators`.
+impl<T: pallet_session::Config> DisableValidator<T::ValidatorId> for pallet_session::Pallet<T> {
+	fn disable(who: &T::ValidatorId) -> bool {
+		Self::disable(who)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Maximum number of validators.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+		/// Validators are not removed if it would leave fewer validators than this.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+		/// Origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Disables offenders until the end of the session.
+		type DisableValidator: DisableValidator<Self::AccountId>;
+		/// The Aura authority identifier type.
+		type AuthorityId: Member + Parameter + RuntimeAppPublic;
+		/// The proof of key ownership, used for validating Aura equivocation reports.
+		type KeyOwnerProof: Parameter + GetSessionNumber + GetValidatorCount;
+		/// Number of past sessions whose first block is kept, i.e. how far back Aura
+		/// equivocations can be reported.
+		#[pallet::constant]
+		type MaxSessionEntries: Get<u32>;
+		/// The Aura equivocation report system.
+		///
+		/// Use `()` to disable equivocation reports.
+		type EquivocationReportSystem: OffenceReportSystem<
+			Option<Self::AccountId>,
+			(AuraEquivocationProof<Self>, Self::KeyOwnerProof),
+		>;
+		/// Type representing the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The validators planned for the upcoming sessions.
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxValidators>, ValueQuery>;
+
+	/// The first block of each of the last `MaxSessionEntries` sessions, to check which session
+	/// a reported equivocation happened in.
+	#[pallet::storage]
+	pub type SessionStartBlock<T: Config> =
+		StorageMap<_, Twox64Concat, SessionIndex, BlockNumberFor<T>, OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial validators.
+		pub validators: Vec<T::AccountId>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			let validators: BoundedVec<_, T::MaxValidators> =
+				self.validators.clone().try_into().expect("Too many genesis validators");
+			<Validators<T>>::put(validators);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was added to the set. [who]
+		ValidatorAdded { who: T::AccountId },
+		/// A validator was removed from the set. [who]
+		ValidatorRemoved { who: T::AccountId },
+		/// A validator was removed from the set for an offence. [who]
+		OffenderRemoved { who: T::AccountId },
+		/// An offender was kept in the set because `MinValidators` would be violated. [who]
+		OffenderKept { who: T::AccountId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a validator.
+		Duplicate,
+		/// The account is not a validator.
+		NotValidator,
+		/// The validator set is full.
+		TooManyValidators,
+		/// Removing the validator would leave fewer than `MinValidators`.
+		TooFewValidators,
+		/// An equivocation proof provided as part of an equivocation report is invalid.
+		InvalidEquivocationProof,
+		/// A key ownership proof provided as part of an equivocation report is invalid.
+		InvalidKeyOwnershipProof,
+		/// A given equivocation report is valid but already previously reported.
+		DuplicateOffenceReport,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add a validator to the set, starting with the next session.
+		///
+		/// The origin for this call must be `AddRemoveOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::add_validator())]
+		pub fn add_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = <Validators<T>>::get();
+			ensure!(!validators.contains(&who), Error::<T>::Duplicate);
+			validators.try_push(who.clone()).map_err(|_| Error::<T>::TooManyValidators)?;
+
+			<Validators<T>>::put(validators);
+			Self::deposit_event(Event::ValidatorAdded { who });
+			Ok(())
+		}
+
+		/// Remove a validator from the set, starting with the next session.
+		///
+		/// The origin for this call must be `AddRemoveOrigin`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_validator())]
+		pub fn remove_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = <Validators<T>>::get();
+			let index =
+				validators.iter().position(|v| v == &who).ok_or(Error::<T>::NotValidator)?;
+			ensure!(
+				validators.len() as u32 > T::MinValidators::get(),
+				Error::<T>::TooFewValidators
+			);
+
+			validators.remove(index);
+			<Validators<T>>::put(validators);
+			Self::deposit_event(Event::ValidatorRemoved { who });
+			Ok(())
+		}
+
+		/// Report an Aura equivocation, i.e. an authority sealing two blocks for the same slot.
+		///
+		/// This extrinsic must be called unsigned and it is expected that only block authors
+		/// will call it (validated in `ValidateUnsigned`), as such if the block author is defined
+		/// it will be defined as the equivocation reporter.
+		#[pallet::call_index(2)]
+		#[pallet::weight(report_equivocation_weight::<T>(key_owner_proof.validator_count()))]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<AuraEquivocationProof<T>>,
+			key_owner_proof: T::KeyOwnerProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			T::EquivocationReportSystem::process_evidence(
+				None,
+				(*equivocation_proof, key_owner_proof),
+			)?;
+			// Waive the fee since the report is valid and beneficial
+			Ok(Pays::No.into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::report_equivocation_unsigned { equivocation_proof, key_owner_proof } = call
+			else {
+				return InvalidTransaction::Call.into()
+			};
+
+			// discard equivocation report not coming from the local node
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => {},
+				_ => {
+					log::warn!(
+						target: "runtime::validator-set",
+						"rejecting unsigned report equivocation transaction because it is not local/in-block."
+					);
+					return InvalidTransaction::Call.into()
+				},
+			}
+
+			let evidence = (*equivocation_proof.clone(), key_owner_proof.clone());
+			T::EquivocationReportSystem::check_evidence(evidence)?;
+
+			let longevity =
+				<T::EquivocationReportSystem as OffenceReportSystem<_, _>>::Longevity::get();
+
+			ValidTransaction::with_tag_prefix("AuraEquivocation")
+				// We assign the maximum priority for any equivocation report.
+				.priority(TransactionPriority::max_value())
+				// Only one equivocation report for the same offender at the same slot.
+				.and_provides((equivocation_proof.offender.clone(), *equivocation_proof.slot))
+				.longevity(longevity)
+				// We don't propagate this. This can never be included on a remote node.
+				.propagate(false)
+				.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			let Call::report_equivocation_unsigned { equivocation_proof, key_owner_proof } = call
+			else {
+				return Err(InvalidTransaction::Call.into())
+			};
+
+			let evidence = (*equivocation_proof.clone(), key_owner_proof.clone());
+			T::EquivocationReportSystem::check_evidence(evidence)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Disable an offender and remove it from the validator set, unless too few validators
+		/// would remain.
+		pub(crate) fn remove_offender(who: &T::AccountId) {
+			let mut validators = <Validators<T>>::get();
+			let Some(index) = validators.iter().position(|v| v == who) else { return };
+
+			if validators.len() as u32 <= T::MinValidators::get() {
+				Self::deposit_event(Event::OffenderKept { who: who.clone() });
+				return
+			}
+
+			validators.remove(index);
+			<Validators<T>>::put(validators);
+			T::DisableValidator::disable(who);
+			Self::deposit_event(Event::OffenderRemoved { who: who.clone() });
+		}
+
+		/// Whether block `number` is part of session `session_index`, as far as the last
+		/// `MaxSessionEntries` sessions are known.
+		pub fn is_in_session(number: BlockNumberFor<T>, session_index: SessionIndex) -> bool {
+			let Some(start) = <SessionStartBlock<T>>::get(session_index) else { return false };
+			let end = <SessionStartBlock<T>>::get(session_index.saturating_add(1));
+			number >= start && end.map_or(true, |end| number < end)
+		}
+	}
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+	/// Always returns the full set, so validators that registered their keys since the last
+	/// rotation are picked up. The session pallet only reports a change to GRANDPA when the
+	/// resulting validators or keys differ.
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(<Validators<T>>::get().into_inner())
+	}
+
+	fn new_session_genesis(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(<Validators<T>>::get().into_inner())
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+
+	fn start_session(start_index: SessionIndex) {
+		<SessionStartBlock<T>>::insert(start_index, <frame_system::Pallet<T>>::block_number());
+		if let Some(expired) = start_index.checked_sub(T::MaxSessionEntries::get()) {
+			<SessionStartBlock<T>>::remove(expired);
+		}
+	}
+}
+
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, ()> for Pallet<T> {
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn new_session_genesis(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as pallet_session::SessionManager<_>>::new_session_genesis(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+}
+
+/// Removes reported offenders from the validator set.
+impl<T: Config, FullIdentification>
+	OnOffenceHandler<T::AccountId, (T::AccountId, FullIdentification), Weight> for Pallet<T>
+{
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::AccountId, FullIdentification)>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+		_disable_strategy: DisableStrategy,
+	) -> Weight {
+		for details in offenders {
+			let (who, _) = &details.offender;
+			log::info!(target: "runtime::validator-set", "Removing offender {:?}", who);
+			Self::remove_offender(who);
+		}
+
+		T::DbWeight::get().reads_writes(offenders.len() as u64, 2 * offenders.len() as u64)
+	}
+}
+
+/// Identifies every validator with `()`: offences only need to know the account.
+pub struct FullIdentificationOf<T>(PhantomData<T>);
+
+impl<T: Config> Convert<T::AccountId, Option<()>> for FullIdentificationOf<T> {
+	fn convert(_validator: T::AccountId) -> Option<()> {
+		Some(())
+	}
+}
@@ -0,0 +1,182 @@
+use crate::{self as pallet_validator_set, AuraEquivocationOffence};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, KeyOwnerProofSystem};
+use sp_core::H256;
+use sp_runtime::{
+	key_types::DUMMY,
+	testing::{TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup, OpaqueKeys},
+	BuildStorage, KeyTypeId,
+};
+use sp_session::MembershipProof;
+use sp_staking::offence::{OffenceError, ReportOffence};
+use std::cell::RefCell;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		ValidatorSet: pallet_validator_set,
+		Session: pallet_session,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_validator_set::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxValidators = ConstU32<10>;
+	type MinValidators = ConstU32<2>;
+	type AddRemoveOrigin = frame_system::EnsureRoot<u64>;
+	type DisableValidator = TestDisableValidator;
+	type AuthorityId = UintAuthorityId;
+	type MaxSessionEntries = ConstU32<3>;
+	type KeyOwnerProof = MembershipProof;
+	type EquivocationReportSystem = pallet_validator_set::EquivocationReportSystem<
+		Self,
+		TestReportOffence,
+		TestKeyOwnerProofSystem,
+		ConstU64<100>,
+	>;
+	type WeightInfo = ();
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = TestXt<RuntimeCall, ()>;
+}
+
+/// Accepts any key ownership proof, identifying authority `UintAuthorityId(v)` as validator `v`.
+pub struct TestKeyOwnerProofSystem;
+
+impl KeyOwnerProofSystem<(KeyTypeId, UintAuthorityId)> for TestKeyOwnerProofSystem {
+	type Proof = MembershipProof;
+	type IdentificationTuple = u64;
+
+	fn prove(_key: (KeyTypeId, UintAuthorityId)) -> Option<Self::Proof> {
+		None
+	}
+
+	fn check_proof(key: (KeyTypeId, UintAuthorityId), _proof: Self::Proof) -> Option<u64> {
+		Some(key.1 .0)
+	}
+}
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<AuraEquivocationOffence<u64>>> = RefCell::new(vec![]);
+}
+
+/// Records the reported offences in `OFFENCES`.
+pub struct TestReportOffence;
+
+impl ReportOffence<u64, u64, AuraEquivocationOffence<u64>> for TestReportOffence {
+	fn report_offence(
+		_reporters: Vec<u64>,
+		offence: AuraEquivocationOffence<u64>,
+	) -> Result<(), OffenceError> {
+		OFFENCES.with(|offences| offences.borrow_mut().push(offence));
+		Ok(())
+	}
+
+	fn is_known_offence(_offenders: &[u64], _time_slot: &sp_consensus_aura::Slot) -> bool {
+		false
+	}
+}
+
+pub fn offences() -> Vec<AuraEquivocationOffence<u64>> {
+	OFFENCES.with(|offences| offences.borrow().clone())
+}
+
+impl pallet_session::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = u64;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<ConstU64<1>, ConstU64<0>>;
+	type SessionManager = ValidatorSet;
+	type SessionHandler = TestSessionHandler;
+	type Keys = UintAuthorityId;
+	type WeightInfo = ();
+}
+
+/// Ignores session changes, the tests only look at the validators.
+pub struct TestSessionHandler;
+
+impl pallet_session::SessionHandler<u64> for TestSessionHandler {
+	const KEY_TYPE_IDS: &'static [KeyTypeId] = &[DUMMY];
+
+	fn on_genesis_session<Ks: OpaqueKeys>(_validators: &[(u64, Ks)]) {}
+
+	fn on_new_session<Ks: OpaqueKeys>(
+		_changed: bool,
+		_validators: &[(u64, Ks)],
+		_queued_validators: &[(u64, Ks)],
+	) {
+	}
+
+	fn on_disabled(_validator_index: u32) {}
+}
+
+thread_local! {
+	pub static DISABLED: RefCell<Vec<u64>> = RefCell::new(vec![]);
+}
+
+/// Records the validators disabled by the pallet in `DISABLED`.
+pub struct TestDisableValidator;
+
+impl pallet_validator_set::DisableValidator<u64> for TestDisableValidator {
+	fn disable(who: &u64) -> bool {
+		DISABLED.with(|disabled| disabled.borrow_mut().push(*who));
+		true
+	}
+}
+
+pub fn disabled() -> Vec<u64> {
+	DISABLED.with(|disabled| disabled.borrow().clone())
+}
+
+// Build genesis storage with validators 1, 2 and 3, each with session keys.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		system: Default::default(),
+		validator_set: pallet_validator_set::GenesisConfig { validators: vec![1, 2, 3] },
+		session: pallet_session::GenesisConfig {
+			keys: (1..=3).map(|v| (v, v, UintAuthorityId(v))).collect(),
+		},
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+	// Go past genesis block so events get deposited
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
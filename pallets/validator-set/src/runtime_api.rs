@@ -0,0 +1,28 @@
+//! Runtime API definition for the validator set pallet.
+//!
+//! Lets the node report Aura equivocations it detects on import, which `AuraApi` doesn't cover.
+
+use crate::OpaqueKeyOwnershipProof;
+use codec::Codec;
+use sp_consensus_slots::EquivocationProof;
+
+sp_api::decl_runtime_apis! {
+	pub trait AuraEquivocationApi<AuthorityId: Codec> {
+		/// Submits an unsigned extrinsic to report an Aura equivocation. The caller must provide
+		/// the equivocation proof and a key ownership proof (obtained with
+		/// `generate_key_ownership_proof`). Returns `None` when the extrinsic couldn't be
+		/// submitted.
+		///
+		/// Only useful in an offchain context.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+			key_owner_proof: OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+
+		/// Generates a proof that `authority_id` was part of the current session's validator set.
+		/// The proof is only valid for equivocations in that session.
+		fn generate_key_ownership_proof(
+			authority_id: AuthorityId,
+		) -> Option<OpaqueKeyOwnershipProof>;
+	}
+}
@@ -0,0 +1,222 @@
+use crate::{check_equivocation_proof, mock::*, Error, Event};
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo};
+use frame_system::pallet_prelude::HeaderFor;
+use pallet_session::SessionManager;
+use sp_consensus_aura::digests::CompatibleDigestItem;
+use sp_consensus_slots::EquivocationProof;
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	traits::Header as _,
+	DigestItem, DispatchError, Perbill,
+};
+use sp_session::MembershipProof;
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+
+type Header = HeaderFor<Test>;
+
+fn report(offender: u64) {
+	<ValidatorSet as OnOffenceHandler<u64, (u64, ()), _>>::on_offence(
+		&[OffenceDetails { offender: (offender, ()), reporters: vec![] }],
+		&[Perbill::from_percent(100)],
+		0,
+		DisableStrategy::WhenSlashed,
+	);
+}
+
+/// A header for `slot` sealed by `author`, with `number` making it unique.
+fn sealed_header(number: u64, slot: u64, author: u64) -> Header {
+	let mut header = Header::new(
+		number,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
+	);
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<TestSignature>>::aura_pre_digest(slot.into()));
+	let signature = TestSignature(author, header.hash().as_ref().to_vec());
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<TestSignature>>::aura_seal(signature));
+	header
+}
+
+#[test]
+fn genesis_validators_start_the_first_session() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			<ValidatorSet as SessionManager<u64>>::new_session_genesis(0),
+			Some(vec![1, 2, 3])
+		);
+		assert_eq!(<ValidatorSet as SessionManager<u64>>::new_session(1), Some(vec![1, 2, 3]));
+	});
+}
+
+#[test]
+fn offenders_are_removed_at_the_next_session() {
+	new_test_ext().execute_with(|| {
+		report(2);
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 3]);
+		assert_eq!(disabled(), vec![2]);
+		System::assert_last_event(Event::OffenderRemoved { who: 2 }.into());
+		assert_eq!(<ValidatorSet as SessionManager<u64>>::new_session(1), Some(vec![1, 3]));
+	});
+}
+
+#[test]
+fn offenders_are_kept_to_respect_min_validators() {
+	new_test_ext().execute_with(|| {
+		report(2);
+		report(3);
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 3]);
+		// Disabling 3 as well would leave too few authorities to produce blocks.
+		assert_eq!(disabled(), vec![2]);
+		System::assert_last_event(Event::OffenderKept { who: 3 }.into());
+	});
+}
+
+#[test]
+fn unknown_offenders_are_ignored() {
+	new_test_ext().execute_with(|| {
+		report(42);
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 2, 3]);
+		assert!(disabled().is_empty());
+	});
+}
+
+#[test]
+fn root_adds_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 2, 3, 4]);
+		System::assert_last_event(Event::ValidatorAdded { who: 4 }.into());
+		assert_eq!(<ValidatorSet as SessionManager<u64>>::new_session(1), Some(vec![1, 2, 3, 4]));
+	});
+}
+
+#[test]
+fn added_validators_join_once_they_set_keys() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+		Session::rotate_session();
+		Session::rotate_session();
+		// 4 has no session keys yet, so the session pallet leaves it out.
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		System::inc_providers(&4);
+		assert_ok!(Session::set_keys(RuntimeOrigin::signed(4), UintAuthorityId(4), vec![]));
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3, 4]);
+	});
+}
+
+#[test]
+fn root_removes_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 1));
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![2, 3]);
+		System::assert_last_event(Event::ValidatorRemoved { who: 1 }.into());
+		assert_eq!(<ValidatorSet as SessionManager<u64>>::new_session(1), Some(vec![2, 3]));
+
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn add_remove_checks_origin_and_membership() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::signed(1), 4),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::signed(1), 3),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), 3),
+			Error::<Test>::Duplicate
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 4),
+			Error::<Test>::NotValidator
+		);
+	});
+}
+
+#[test]
+fn equivocation_proofs_are_checked() {
+	let proof = |offender: u64, first_header, second_header| EquivocationProof {
+		offender: UintAuthorityId(offender),
+		slot: 7.into(),
+		first_header,
+		second_header,
+	};
+
+	assert!(check_equivocation_proof(proof(1, sealed_header(1, 7, 1), sealed_header(2, 7, 1))));
+	// Same header twice.
+	assert!(!check_equivocation_proof(proof(1, sealed_header(1, 7, 1), sealed_header(1, 7, 1))));
+	// Different slots.
+	assert!(!check_equivocation_proof(proof(1, sealed_header(1, 7, 1), sealed_header(2, 8, 1))));
+	// Sealed by someone else.
+	assert!(!check_equivocation_proof(proof(1, sealed_header(1, 7, 1), sealed_header(2, 7, 2))));
+}
+
+/// Report validator 2 sealing blocks `number` and `number + 1` for the same slot, with a key
+/// ownership proof for `session`.
+fn report_equivocation(number: u64, session: SessionIndex) -> DispatchResultWithPostInfo {
+	let equivocation_proof = EquivocationProof {
+		offender: UintAuthorityId(2),
+		slot: 7.into(),
+		first_header: sealed_header(number, 7, 2),
+		second_header: sealed_header(number + 1, 7, 2),
+	};
+	let key_owner_proof = MembershipProof { session, trie_nodes: vec![], validator_count: 3 };
+	ValidatorSet::report_equivocation_unsigned(
+		RuntimeOrigin::none(),
+		Box::new(equivocation_proof),
+		key_owner_proof,
+	)
+}
+
+#[test]
+fn equivocations_are_reported_for_their_session() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		Session::rotate_session();
+
+		assert_ok!(report_equivocation(5, 1));
+		let offences = offences();
+		assert_eq!(offences.len(), 1);
+		assert_eq!((offences[0].offender, offences[0].session_index), (2, 1));
+	});
+}
+
+#[test]
+fn equivocations_proven_for_another_session_are_rejected() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		Session::rotate_session();
+
+		// Blocks 3 and 4 belong to session 0, which ended at block 5.
+		assert_noop!(report_equivocation(3, 1), Error::<Test>::InvalidEquivocationProof);
+		assert_noop!(report_equivocation(5, 0), Error::<Test>::InvalidEquivocationProof);
+		// Straddling both sessions.
+		assert_noop!(report_equivocation(4, 0), Error::<Test>::InvalidEquivocationProof);
+		assert!(offences().is_empty());
+	});
+}
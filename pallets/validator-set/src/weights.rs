@@ -0,0 +1,57 @@
+//! Placeholder weights for pallet_validator_set.
+//!
+//! These are hand-written, not generated by the benchmark CLI, since the pallet hasn't been
+//! benchmarked on reference hardware yet. Both calls decode, scan and re-encode `Validators`, so
+//! their ref time is set to twice that of a benchmarked call touching one storage value of this
+//! size, and their proof size covers `Validators` at `MaxValidators` = 32 accounts (1025 bytes)
+//! plus the trie nodes proving it, rounded up. The database reads and writes are counted from the
+//! code and listed on each function.
+//!
+//! TODO: Replace with the output of `benchmark pallet --pallet pallet_validator_set` using the
+//! benchmarks in `benchmarking.rs`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_validator_set.
+pub trait WeightInfo {
+	fn add_validator() -> Weight;
+	fn remove_validator() -> Weight;
+}
+
+/// Weights for pallet_validator_set using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: ValidatorSet Validators (r:1 w:1)
+	fn add_validator() -> Weight {
+		Weight::from_parts(30_000_000, 3_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: ValidatorSet Validators (r:1 w:1)
+	fn remove_validator() -> Weight {
+		Weight::from_parts(30_000_000, 3_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: ValidatorSet Validators (r:1 w:1)
+	fn add_validator() -> Weight {
+		Weight::from_parts(30_000_000, 3_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: ValidatorSet Validators (r:1 w:1)
+	fn remove_validator() -> Weight {
+		Weight::from_parts(30_000_000, 3_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,111 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Vesting Genesis Pallet
+//!
+//! Sets `pallet_vesting` schedules with a cliff at genesis, which `pallet_vesting` can't do
+//! itself: its schedules unlock linearly from their first block on, and its genesis sets a single
+//! schedule per entry.
+//!
+//! A schedule of `locked` funds starting at `start` with a cliff at `cliff` unlocks nothing
+//! before `cliff`, everything accrued since `start` at `cliff`, and `per_block` per block after,
+//! i.e. from `cliff` on exactly as much as a schedule starting at `start` without a cliff. It is
+//! set as two `pallet_vesting` schedules:
+//!
+//! - the accrued `per_block * (cliff - start)`, unlocked at once at `cliff`, i.e. a schedule
+//!   starting at `cliff - 1` unlocking all of it in one block,
+//! - the rest, unlocking `per_block` per block from `cliff` on.
+//!
+//! A schedule without a cliff, i.e. `cliff == start`, is set as a single schedule.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::{Currency, VestingSchedule};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::traits::{Convert, One, Saturating, Zero};
+use sp_std::prelude::*;
+
+type BalanceOf<T> = <<T as pallet_vesting::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_vesting::Config {}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The vesting schedules: `(account, start, cliff, locked, per_block)`.
+		pub vesting:
+			Vec<(T::AccountId, BlockNumberFor<T>, BlockNumberFor<T>, BalanceOf<T>, BalanceOf<T>)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (who, start, cliff, locked, per_block) in &self.vesting {
+				Pallet::<T>::add_vesting_schedule(who, *start, *cliff, *locked, *per_block)
+					.unwrap_or_else(|e| {
+						panic!("Failed to set the genesis vesting schedule of {:?}: {}", who, e)
+					});
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn add_vesting_schedule(
+		who: &T::AccountId,
+		start: BlockNumberFor<T>,
+		cliff: BlockNumberFor<T>,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+	) -> Result<(), &'static str> {
+		if cliff < start {
+			return Err("the cliff is before the start")
+		}
+		if locked.is_zero() || per_block.is_zero() {
+			return Err("it must lock and unlock a non-zero amount")
+		}
+
+		let accrued = per_block
+			.saturating_mul(T::BlockNumberToBalance::convert(cliff - start))
+			.min(locked);
+		if !accrued.is_zero() {
+			// `cliff > start`, so `cliff - 1` doesn't underflow.
+			Self::add(who, accrued, accrued, cliff - One::one())?;
+		}
+		let rest = locked - accrued;
+		if !rest.is_zero() {
+			Self::add(who, rest, per_block, cliff)?;
+		}
+		Ok(())
+	}
+
+	fn add(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: BlockNumberFor<T>,
+	) -> Result<(), &'static str> {
+		<pallet_vesting::Pallet<T> as VestingSchedule<T::AccountId>>::add_vesting_schedule(
+			who,
+			locked,
+			per_block,
+			starting_block,
+		)
+		.map_err(|_| "too many vesting schedules")
+	}
+}
@@ -0,0 +1,56 @@
+use crate::mock::*;
+use frame_support::traits::VestingSchedule;
+
+fn locked_at(block: u64) -> u128 {
+	System::set_block_number(block);
+	Vesting::vesting_balance(&1).unwrap_or_default()
+}
+
+#[test]
+fn genesis_sets_schedules_without_a_cliff() {
+	new_test_ext(vec![(1, 10, 10, 500, 10)]).execute_with(|| {
+		assert_eq!(Vesting::vesting(1).unwrap().len(), 1);
+		assert_eq!(Balances::locks(1)[0].amount, 500);
+
+		assert_eq!(locked_at(10), 500);
+		assert_eq!(locked_at(11), 490);
+		assert_eq!(locked_at(60), 0);
+	});
+}
+
+#[test]
+fn genesis_unlocks_the_accrued_amount_at_the_cliff() {
+	new_test_ext(vec![(1, 10, 20, 500, 10)]).execute_with(|| {
+		assert_eq!(Vesting::vesting(1).unwrap().len(), 2);
+		assert_eq!(Balances::locks(1)[0].amount, 500);
+
+		assert_eq!(locked_at(11), 500);
+		assert_eq!(locked_at(19), 500);
+		// As much as without a cliff from the cliff on.
+		assert_eq!(locked_at(20), 400);
+		assert_eq!(locked_at(21), 390);
+		assert_eq!(locked_at(60), 0);
+	});
+}
+
+#[test]
+fn genesis_unlocks_everything_at_a_cliff_after_the_end() {
+	new_test_ext(vec![(1, 0, 100, 500, 10)]).execute_with(|| {
+		assert_eq!(Vesting::vesting(1).unwrap().len(), 1);
+
+		assert_eq!(locked_at(99), 500);
+		assert_eq!(locked_at(100), 0);
+	});
+}
+
+#[test]
+#[should_panic(expected = "the cliff is before the start")]
+fn genesis_rejects_a_cliff_before_the_start() {
+	new_test_ext(vec![(1, 20, 10, 500, 10)]);
+}
+
+#[test]
+#[should_panic(expected = "must lock and unlock a non-zero amount")]
+fn genesis_rejects_empty_schedules() {
+	new_test_ext(vec![(1, 0, 0, 500, 0)]);
+}
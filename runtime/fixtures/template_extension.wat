@@ -0,0 +1,38 @@
+;; Calls the `TemplateExtension` function whose id is the first 4 bytes of the input with the rest
+;; of the input, and returns the function's output.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "call_chain_extension"
+		(func $call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\00\01")
+
+	;; [4, 260) input buffer: the function id, then its input
+
+	;; [260, 264) length of the output buffer
+	(data (i32.const 260) "\10")
+
+	;; [264, 280) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(drop
+			(call $call_chain_extension
+				(i32.load (i32.const 4))                         ;; id
+				(i32.const 8)                                    ;; input_ptr
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4)) ;; input_len
+				(i32.const 264)                                  ;; output_ptr
+				(i32.const 260)                                  ;; output_len_ptr
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 264) (i32.load (i32.const 260)))
+	)
+)
@@ -0,0 +1,53 @@
+//! The chain extension giving contracts access to `pallet_template`'s `Something`.
+//!
+//! Contracts call its functions by id, e.g. through an ink! `#[ink::chain_extension]` with
+//! `#[ink(extension = <id>)]` functions:
+//!
+//! - `1`: `something() -> Option<u32>` reads the global `Something`.
+//! - `2`: `do_something(something: u32)` stores `something` on behalf of the contract, as a
+//!   `TemplateModule::do_something` call signed by the contract's account would, holding the
+//!   storage deposit from the contract's balance.
+
+use crate::{Runtime, RuntimeCall, RuntimeOrigin, TemplateModule};
+use codec::Encode;
+use frame_support::{dispatch::GetDispatchInfo, traits::Get};
+use pallet_contracts::chain_extension::{ChainExtension, Environment, Ext, InitState, RetVal};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+/// Read the global `Something`.
+const SOMETHING: u16 = 1;
+/// Dispatch `do_something` as the contract.
+const DO_SOMETHING: u16 = 2;
+
+/// Exposes reads and writes of `pallet_template`'s `Something` to contracts.
+#[derive(Default)]
+pub struct TemplateExtension;
+
+impl ChainExtension<Runtime> for TemplateExtension {
+	fn call<E: Ext<T = Runtime>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		match env.func_id() {
+			SOMETHING => {
+				let mut env = env.buf_in_buf_out();
+				env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+				env.write(&TemplateModule::something().encode(), false, None)?;
+			},
+			DO_SOMETHING => {
+				let mut env = env.buf_in_buf_out();
+				let something: u32 = env.read_as()?;
+				let call =
+					RuntimeCall::TemplateModule(pallet_template::Call::do_something { something });
+				env.charge_weight(call.get_dispatch_info().weight)?;
+
+				// Dispatched like a signed transaction, so the call filter still applies.
+				let origin = RuntimeOrigin::signed(env.ext().address().clone());
+				call.dispatch(origin).map_err(|e| e.error)?;
+			},
+			_ => return Err(DispatchError::Other("Unknown TemplateExtension function")),
+		}
+
+		Ok(RetVal::Converging(0))
+	}
+}
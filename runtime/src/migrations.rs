@@ -0,0 +1,131 @@
+//! Runtime-level storage migrations, for changes spanning several pallets.
+
+use crate::{opaque::SessionKeys, AccountId, Aura, Grandpa, Runtime, Session};
+#[cfg(feature = "try-runtime")]
+use codec::{Decode, Encode};
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+use frame_support::{traits::OnRuntimeUpgrade, weights::Weight, BoundedVec};
+use sp_runtime::traits::OpaqueKeys;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+use sp_std::vec::Vec;
+
+/// `pallet_session` storage seeded by [`SeedValidatorsFromAuthorities`], which
+/// `pallet_session` only writes at genesis and on rotation.
+mod session {
+	use super::*;
+	use frame_support::{pallet_prelude::ValueQuery, storage_alias, Twox64Concat};
+	use sp_core::crypto::KeyTypeId;
+
+	#[storage_alias]
+	pub type Validators = StorageValue<Session, Vec<AccountId>, ValueQuery>;
+
+	#[storage_alias]
+	pub type QueuedKeys = StorageValue<Session, Vec<(AccountId, SessionKeys)>, ValueQuery>;
+
+	#[storage_alias]
+	pub type NextKeys = StorageMap<Session, Twox64Concat, AccountId, SessionKeys>;
+
+	#[storage_alias]
+	pub type KeyOwner = StorageMap<Session, Twox64Concat, (KeyTypeId, Vec<u8>), AccountId>;
+}
+
+/// Seeds `ValidatorSet` and `Session` from the Aura and GRANDPA authorities of a chain started
+/// before those pallets were added, which have no genesis state there.
+///
+/// Without it the first session rotation would hand Aura and GRANDPA an empty authority set and
+/// the chain would stall. Each authority is registered with its current Aura and GRANDPA keys as
+/// both the active and the queued validators, so rotations don't change the authorities until
+/// `ValidatorSet` does.
+///
+/// A validator's account is the account of its Aura key, as in the node's chain specs. Does
+/// nothing once `ValidatorSet` has validators, e.g. on chains with a genesis for it.
+pub struct SeedValidatorsFromAuthorities;
+
+impl OnRuntimeUpgrade for SeedValidatorsFromAuthorities {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		if !pallet_validator_set::Validators::<Runtime>::get().is_empty() {
+			log::info!(target: "runtime::migrations", "Validator set already seeded, skipping");
+			return db_weight.reads(1)
+		}
+
+		let grandpa = Grandpa::grandpa_authorities();
+		let authorities = Aura::authorities().into_iter().zip(grandpa);
+		let mut queued_keys = Vec::new();
+		for (aura, (grandpa, _weight)) in authorities {
+			let who: AccountId = sp_core::sr25519::Public::from(aura.clone()).into();
+			let keys = SessionKeys { aura, grandpa };
+
+			// Register the keys as `pallet_session`'s genesis does, without `set_keys`' deposit
+			// of a consumer reference. Only accounts that exist take one, so none is leaked.
+			for id in SessionKeys::key_ids() {
+				session::KeyOwner::insert((*id, keys.get_raw(*id).to_vec()), &who);
+			}
+			session::NextKeys::insert(&who, &keys);
+			if frame_system::Pallet::<Runtime>::account_exists(&who) {
+				let _ = frame_system::Pallet::<Runtime>::inc_consumers_without_limit(&who);
+			}
+			queued_keys.push((who, keys));
+		}
+
+		let validators: Vec<_> = queued_keys.iter().map(|(who, _)| who.clone()).collect();
+		log::info!(target: "runtime::migrations", "Seeded {} validators", validators.len());
+		pallet_validator_set::Validators::<Runtime>::put(BoundedVec::truncate_from(
+			validators.clone(),
+		));
+		session::Validators::put(validators);
+		session::QueuedKeys::put(queued_keys.clone());
+		// The current session has been running since genesis, so equivocations in any earlier
+		// block can be reported for it.
+		pallet_validator_set::SessionStartBlock::<Runtime>::insert(Session::current_index(), 0);
+
+		// Per validator, reads and writes the account, and writes its keys and both key owners.
+		let count = queued_keys.len() as u64;
+		db_weight.reads_writes(4 + count, 4 + 4 * count)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+		Ok(pallet_validator_set::Validators::<Runtime>::get().is_empty().encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		let seeded =
+			bool::decode(&mut &state[..]).map_err(|_| "Failed to decode the pre-upgrade state")?;
+		if !seeded {
+			return Ok(())
+		}
+
+		let validators = pallet_validator_set::Validators::<Runtime>::get().into_inner();
+		ensure!(!validators.is_empty(), "The validator set must not be empty");
+		let aura_accounts: Vec<AccountId> = Aura::authorities()
+			.into_iter()
+			.map(|aura| sp_core::sr25519::Public::from(aura).into())
+			.collect();
+		ensure!(
+			validators == aura_accounts,
+			"The validator set must be the accounts of the Aura authorities"
+		);
+		ensure!(
+			Session::validators() == validators,
+			"The session validators must match the validator set"
+		);
+
+		let queued_aura: Vec<_> =
+			Session::queued_keys().into_iter().map(|(_, keys)| keys.aura).collect();
+		ensure!(
+			queued_aura == Aura::authorities().into_inner(),
+			"The queued Aura keys must match the Aura authorities"
+		);
+		for (who, keys) in Session::queued_keys() {
+			ensure!(
+				session::NextKeys::get(&who) == Some(keys),
+				"The next session keys must match the queued keys"
+			);
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,160 @@
+use crate::{
+	AccountId, Address, Balances, BlockWeights, BuildStorage, Contracts, ProxyType, Runtime,
+	RuntimeCall, RuntimeHoldReason, System, TemplateModule, UNIT,
+};
+use codec::Encode;
+use frame_support::traits::{fungible::InspectHold, Currency, InstanceFilter};
+use pallet_contracts::{CollectEvents, DebugInfo, Determinism};
+use pallet_contracts_primitives::Code;
+use sp_runtime::AccountId32;
+
+fn dest() -> Address {
+	Address::Id(AccountId32::new([1; 32]))
+}
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn batch(calls: Vec<RuntimeCall>) -> RuntimeCall {
+	RuntimeCall::Utility(pallet_utility::Call::batch_all { calls })
+}
+
+#[test]
+fn non_transfer_proxies_cannot_move_funds() {
+	let transfers = [
+		RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+			dest: dest(),
+			value: 1,
+		}),
+		RuntimeCall::Vesting(pallet_vesting::Call::vested_transfer {
+			target: dest(),
+			schedule: pallet_vesting::VestingInfo::new(10, 1, 0),
+		}),
+		RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: codec::Compact(1),
+			target: dest(),
+			amount: 1,
+		}),
+	];
+
+	for call in transfers {
+		assert!(!ProxyType::NonTransfer.filter(&call));
+		// Nor within a batch, however deeply nested.
+		assert!(!ProxyType::NonTransfer.filter(&batch(vec![remark(), call.clone()])));
+		assert!(!ProxyType::NonTransfer.filter(&batch(vec![batch(vec![call])])));
+	}
+}
+
+#[test]
+fn non_transfer_proxies_can_vote() {
+	let calls = vec![
+		remark(),
+		RuntimeCall::Democracy(pallet_democracy::Call::remove_vote { index: 0 }),
+		RuntimeCall::Council(pallet_collective::Call::vote {
+			proposal: Default::default(),
+			index: 0,
+			approve: true,
+		}),
+		RuntimeCall::Identity(pallet_identity::Call::clear_identity {}),
+	];
+
+	for call in &calls {
+		assert!(ProxyType::NonTransfer.filter(call));
+	}
+	assert!(ProxyType::NonTransfer.filter(&batch(calls)));
+}
+
+#[test]
+fn template_only_proxies_are_limited_to_the_template_pallet() {
+	let call = RuntimeCall::TemplateModule(pallet_template::Call::do_something { something: 42 });
+	assert!(ProxyType::TemplateOnly.filter(&batch(vec![call.clone()])));
+	assert!(!ProxyType::TemplateOnly.filter(&batch(vec![call, remark()])));
+	assert!(ProxyType::NonTransfer.is_superset(&ProxyType::TemplateOnly));
+	assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::CancelProxy));
+}
+
+fn alice() -> AccountId {
+	AccountId32::new([2; 32])
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(alice(), 1_000 * UNIT)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// Instantiate `fixtures/template_extension.wat`, endowed with one unit, as Alice.
+fn instantiate_extension_fixture() -> AccountId {
+	let code = wat::parse_str(include_str!("../fixtures/template_extension.wat")).unwrap();
+	Contracts::bare_instantiate(
+		alice(),
+		UNIT,
+		BlockWeights::get().max_block,
+		None,
+		Code::Upload(code),
+		vec![],
+		vec![],
+		DebugInfo::Skip,
+		CollectEvents::Skip,
+	)
+	.result
+	.unwrap()
+	.account_id
+}
+
+// Have the fixture call the chain extension function `func_id` with `input`, returning its output.
+fn call_extension(contract: &AccountId, func_id: u32, input: impl Encode) -> Vec<u8> {
+	Contracts::bare_call(
+		alice(),
+		contract.clone(),
+		0,
+		BlockWeights::get().max_block,
+		None,
+		(func_id, input).encode(),
+		DebugInfo::Skip,
+		CollectEvents::Skip,
+		Determinism::Enforced,
+	)
+	.result
+	.unwrap()
+	.data
+}
+
+#[test]
+fn contracts_read_and_write_something_through_the_chain_extension() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate_extension_fixture();
+		assert_eq!(call_extension(&contract, 1, ()), None::<u32>.encode());
+
+		call_extension(&contract, 2, 42u32);
+		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::account_something(&contract), Some(42));
+		assert_eq!(call_extension(&contract, 1, ()), Some(42u32).encode());
+	});
+}
+
+#[test]
+fn the_chain_extension_holds_the_deposit_from_the_contract() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate_extension_fixture();
+		let alice_balance = Balances::free_balance(alice());
+		let contract_balance = Balances::free_balance(&contract);
+		let reason = RuntimeHoldReason::TemplateModule(pallet_template::HoldReason::StorageDeposit);
+
+		call_extension(&contract, 2, 42u32);
+		let deposit = TemplateModule::deposit(&contract);
+		assert!(deposit > 0);
+		assert_eq!(Balances::balance_on_hold(&reason, &contract), deposit);
+		assert_eq!(Balances::free_balance(&contract), contract_balance - deposit);
+		assert_eq!(Balances::free_balance(alice()), alice_balance);
+
+		// Only a new value takes a deposit.
+		call_extension(&contract, 2, 43u32);
+		assert_eq!(Balances::balance_on_hold(&reason, &contract), deposit);
+	});
+}